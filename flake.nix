@@ -17,6 +17,7 @@
         packages = with pkgs; [
           pkg-config
           openssl_3
+          stockfish
         ];
       in
       {
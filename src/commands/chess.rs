@@ -1,14 +1,21 @@
-use chess::{ChessMove, Color, Piece, Square};
-use futures_util::{SinkExt, StreamExt};
+use chess::Color;
+use once_cell::sync::Lazy;
 use regex::Regex;
-use reywen::{
-    client::methods::message::DataMessageSend,
-    structures::channels::message::{Message, Reply},
-    websocket::data::{WebSocketEvent, WebSocketSend},
+use reywen::structures::channels::message::Message;
+
+use super::{Command, Error};
+use crate::{
+    engine::Difficulty,
+    game::{
+        chess960,
+        clock::{Clock, TimeControl},
+        Game,
+    },
+    render::{self, RenderMode},
+    Client, ResourceType,
 };
 
-use super::{Command, Error, PREFIX};
-use crate::{Client, ResourceType};
+static FEN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"--fen\s+"([^"]*)""#).unwrap());
 
 #[derive(Default)]
 pub struct Chess;
@@ -24,174 +31,209 @@ impl Command for Chess {
     }
 
     fn get_usage(&self) -> String {
-        "[white|black|random] <opponent>".to_string()
+        "[white|black|random] <opponent> [time control] [beginner|easy|medium|hard|strong] [--delay] [--casual] [--fen \"<FEN>\"|--960 [position]] [--text|--image]".to_string()
     }
 
     async fn execute(&self, client: &Client, message: &Message) -> Result<(), super::Error> {
-        let Some(content) = &message.content else {
-            return Ok(());
-        };
-
-        let mut args = content.split_whitespace();
-
-        // the command argument
-        if args.next().is_none() {
-            return Ok(());
-        }
-
-        let Some(p1_color) = args.next() else {
-            return Err(Error::InvalidUsage {
-                message: String::from("Color argument needed."),
-                usage: self.get_usage(),
-            });
-        };
-
-        let Some(p1_color) = get_color(p1_color) else {
-            return Err(Error::InvalidUsage {
-                message: format!("Unexpected color \"{p1_color}\"."),
-                usage: self.get_usage(),
-            });
-        };
+        create_game(client, message, &self.get_usage(), false).await
+    }
+}
 
-        let Some(p2) = args.next() else {
-            return Err(Error::InvalidUsage {
-                message: String::from("Opponent argument needed"),
-                usage: self.get_usage(),
-            });
-        };
+/// Parses the arguments shared by `chess` and `chess960` and challenges the opponent.
+pub(super) async fn create_game(
+    client: &Client,
+    message: &Message,
+    usage: &str,
+    mut is_chess960: bool,
+) -> Result<(), Error> {
+    let Some(content) = &message.content else {
+        return Ok(());
+    };
+
+    let fen = FEN_REGEX
+        .captures(content)
+        .map(|captures| captures[1].to_string());
+    let content = FEN_REGEX.replace(content, "");
+
+    let mut args = content.split_whitespace();
+
+    // the command argument
+    if args.next().is_none() {
+        return Ok(());
+    }
 
-        let p2 = match client.resolve_user(p2).await {
+    let Some(p1_color) = args.next() else {
+        return Err(Error::InvalidUsage {
+            message: String::from("Color argument needed."),
+            usage: usage.to_string(),
+        });
+    };
+
+    let Some(p1_color) = get_color(p1_color) else {
+        return Err(Error::InvalidUsage {
+            message: format!("Unexpected color \"{p1_color}\"."),
+            usage: usage.to_string(),
+        });
+    };
+
+    let Some(p2) = args.next() else {
+        return Err(Error::InvalidUsage {
+            message: String::from("Opponent argument needed"),
+            usage: usage.to_string(),
+        });
+    };
+
+    let p2 = if p2.eq_ignore_ascii_case("stockfish") {
+        client.user.clone()
+    } else {
+        match client.resolve_user(p2).await {
             Ok(Some(p2)) => p2,
             Ok(None) => return Err(Error::Generic(String::from("Failed to find user."))),
-            Err(error) => return Err(Error::Fetch {
-                resource: ResourceType::User,
-                inner: error
-            }),
-        };
-
-        let p1 = match client.fetch_user(&message.author).await {
-            Ok(p) => p,
             Err(error) => {
                 return Err(Error::Fetch {
                     resource: ResourceType::User,
                     inner: error,
-                });
+                })
             }
-        };
-
-        let mut game = chess::Game::new();
-        let mut current_turn_is_p1 = matches!(p1_color, Color::White);
-
-        loop {
-            let (mut read, write) = client.driver.websocket.dual_async().await;
-
-            while let Some(event) = read.next().await {
-                match event {
-                    WebSocketEvent::Ready { .. } => {
-                        let _ = write
-                            .lock()
-                            .await
-                            .send(WebSocketSend::Ping { data: 0 }.into())
-                            .await;
-                    }
-                    WebSocketEvent::Pong { data } => {
-                        let write = write.clone();
-                        tokio::spawn(async move {
-                            tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                            let _ = write
-                                .lock()
-                                .await
-                                .send(WebSocketSend::Ping { data }.into())
-                                .await;
-                        });
-                    }
-                    WebSocketEvent::Message { message } => {
-                        let Some(content) = message.content else {
-                            continue;
-                        };
-
-                        let user_id = if current_turn_is_p1 { &p1.id } else { &p2.id };
-                        if message.author.as_str() != user_id {
-                            continue;
-                        }
-
-                        let mut args = content.split_whitespace();
-
-                        if !args
-                            .next()
-                            .is_some_and(|command| *command == format!("{PREFIX}move"))
-                        {
-                            continue;
-                        }
-
-                        let replies = vec![Reply {
-                            id: message.id,
-                            mention: true,
-                        }];
-
-                        let Some(uci_move) = args.next() else {
-                            let _ = client
-                                .driver
-                                .message_send(
-                                    &message.channel,
-                                    &DataMessageSend::new().set_content(&format!("Expected UCI move argument. Usage:\n> {PREFIX}move [a-h][1-8][a-h][1-8](r|n|b|q)?")).set_replies(replies),
-                                )
-                                .await;
-                            continue;
-                        };
-
-                        let Some(captures) = Regex::new("^([a-h][1-8])([a-h][1-8])(r|n|b|q)?$")
-                            .unwrap()
-                            .captures(uci_move)
-                        else {
-                            println!("invalid move");
-                            continue;
-                        };
-
-                        let Some(start_square) = captures.get(1) else {
-                            println!("no captures[1]");
-                            continue;
-                        };
-
-                        let Some(target_square) = captures.get(2) else {
-                            println!("no captures[2]");
-                            continue;
-                        };
-
-                        let promotion = captures.get(3).map(|promotion| match promotion.as_str() {
-                            "r" => Piece::Rook,
-                            "n" => Piece::Knight,
-                            "b" => Piece::Bishop,
-                            "q" => Piece::Queen,
-                            _ => unreachable!(),
-                        });
-
-                        let mut start_square = start_square.as_str().chars();
-                        let mut target_square = target_square.as_str().chars();
-
-                        let start_file = start_square.next().unwrap() as u8 - b'a';
-                        let start_rank = start_square.next().unwrap() as u8 - b'0';
-                        let target_file = target_square.next().unwrap() as u8 - b'a';
-                        let target_rank = target_square.next().unwrap() as u8 - b'0';
-
-                        let (start_square, target_square) = unsafe {
-                            (
-                                Square::new(start_file + start_rank * 8),
-                                Square::new(target_file + target_rank * 8),
-                            )
-                        };
-
-                        println!("{:?} {:?}", start_square, target_square);
-                        game.make_move(ChessMove::new(start_square, target_square, promotion));
-
-                        println!("{:?}", game.current_position());
-                        current_turn_is_p1 = !current_turn_is_p1;
-                    }
-                    _ => {}
-                }
+        }
+    };
+
+    let p1 = match client.fetch_user(&message.author).await {
+        Ok(p) => p,
+        Err(error) => {
+            return Err(Error::Fetch {
+                resource: ResourceType::User,
+                inner: error,
+            });
+        }
+    };
+
+    let mut render_mode = None;
+    let mut time_control = None;
+    let mut delay = false;
+    let mut casual = false;
+    let mut position = None;
+    let mut difficulty = None;
+
+    for flag in args {
+        match flag {
+            "--text" => render_mode = Some(RenderMode::Text),
+            "--image" => render_mode = Some(RenderMode::Image),
+            "--delay" => delay = true,
+            "--casual" => casual = true,
+            "--960" => is_chess960 = true,
+            "--fen" => {
+                return Err(Error::InvalidUsage {
+                    message: String::from("The FEN has to be in quotes, like `--fen \"<FEN>\"`."),
+                    usage: usage.to_string(),
+                })
             }
+            flag => match (
+                flag.parse::<TimeControl>(),
+                flag.parse::<u16>(),
+                flag.parse::<Difficulty>(),
+            ) {
+                (Ok(control), _, _) => time_control = Some(control),
+                (_, Ok(index), _) if index < chess960::POSITIONS => position = Some(index),
+                (_, _, Ok(level)) => difficulty = Some(level),
+                _ => {
+                    return Err(Error::InvalidUsage {
+                        message: format!("Unexpected argument \"{flag}\"."),
+                        usage: usage.to_string(),
+                    })
+                }
+            },
         }
     }
+
+    let server = client
+        .fetch_server_id(&message.channel)
+        .await
+        .map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?;
+
+    let render_mode = match render_mode {
+        Some(render_mode) => render_mode,
+        None => render::server_mode(&client.cache, &server).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?,
+    };
+
+    // Games against the bot or yourself never count towards ratings.
+    let rated = !casual && p2.id != client.user.id && p2.id != p1.id;
+
+    if difficulty.is_some() && p2.id != client.user.id {
+        return Err(Error::InvalidUsage {
+            message: String::from("Difficulties are only for games against the bot."),
+            usage: usage.to_string(),
+        });
+    }
+
+    let (white, black) = match p1_color {
+        Color::White => (p1, p2),
+        Color::Black => (p2, p1),
+    };
+
+    if position.is_some() && !is_chess960 {
+        return Err(Error::InvalidUsage {
+            message: String::from(
+                "Starting positions can only be chosen for Chess960, add `--960`.",
+            ),
+            usage: usage.to_string(),
+        });
+    }
+
+    if is_chess960 && fen.is_some() {
+        return Err(Error::InvalidUsage {
+            message: String::from("`--fen` and `--960` cannot be combined."),
+            usage: usage.to_string(),
+        });
+    }
+
+    let start_fen = is_chess960.then(|| {
+        let index = position.unwrap_or_else(|| rand::random::<u16>() % chess960::POSITIONS);
+
+        chess960::start_fen(index).unwrap_or_default()
+    });
+
+    let mut game = match (fen, start_fen) {
+        (_, Some(fen)) => Game::chess960(
+            message.id.clone(),
+            message.channel.clone(),
+            white,
+            black,
+            &fen,
+        )
+        .map_err(|error| Error::Generic(format!("Invalid Chess960 position: {error}")))?,
+        (Some(fen), None) => Game::from_fen(
+            message.id.clone(),
+            message.channel.clone(),
+            white,
+            black,
+            &fen,
+        )
+        .map_err(|error| Error::InvalidUsage {
+            message: format!("Invalid FEN: {error}"),
+            usage: usage.to_string(),
+        })?,
+        (None, None) => Game::new(message.id.clone(), message.channel.clone(), white, black),
+    };
+    game.server = server;
+    game.render_mode = render_mode;
+    game.difficulty = difficulty;
+    game.rated = rated;
+    game.clock = time_control.map(|time_control| {
+        Clock::new(if delay {
+            time_control.with_delay()
+        } else {
+            time_control
+        })
+    });
+
+    client.games.challenge(client, message, game).await
 }
 
 fn get_color(color: &str) -> Option<Color> {
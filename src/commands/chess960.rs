@@ -0,0 +1,26 @@
+use reywen::structures::channels::message::Message;
+
+use super::{chess::create_game, Command, Error};
+use crate::Client;
+
+pub struct Chess960;
+
+#[async_trait::async_trait]
+impl Command for Chess960 {
+    fn get_name(&self) -> String {
+        "chess960".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["fischer-random".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[white|black|random] <opponent> [position] [time control] [beginner|easy|medium|hard|strong] [--delay] [--text|--image]"
+            .to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        create_game(client, message, &self.get_usage(), true).await
+    }
+}
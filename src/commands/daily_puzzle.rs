@@ -0,0 +1,169 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    puzzle::daily::{self, DailyPuzzle},
+    Client, ResourceType,
+};
+
+use super::{Command, Error};
+
+#[derive(Default)]
+pub struct DailyPuzzleCommand;
+
+#[async_trait::async_trait]
+impl Command for DailyPuzzleCommand {
+    fn get_name(&self) -> String {
+        "daily-puzzle".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[set <channel> <HH:MM>|stop]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mut args = content.split_whitespace();
+
+        if args.next().is_none() {
+            return Ok(());
+        }
+
+        let server = client
+            .fetch_server_id(&message.channel)
+            .await
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Channel,
+                inner: error,
+            })?;
+
+        let current = daily::load(&client.cache, &server).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?;
+
+        let action = args.next();
+
+        if action.is_some() {
+            super::server_owned_by_author(client, message, "daily puzzle").await?;
+        }
+
+        let content = match action {
+            Some("set") => {
+                let (Some(channel), Some(time)) = (args.next(), args.next()) else {
+                    return Err(Error::InvalidUsage {
+                        message: String::from("Channel and time arguments needed."),
+                        usage: self.get_usage(),
+                    });
+                };
+
+                let channel = match client.resolve_channel(channel).await {
+                    Ok(Some(channel)) => channel.id().to_string(),
+                    Ok(None) => {
+                        return Err(Error::Generic(String::from("Failed to find channel.")))
+                    }
+                    Err(error) => {
+                        return Err(Error::Fetch {
+                            resource: ResourceType::Channel,
+                            inner: error,
+                        })
+                    }
+                };
+
+                let channel_server =
+                    client
+                        .fetch_server_id(&channel)
+                        .await
+                        .map_err(|error| Error::Fetch {
+                            resource: ResourceType::Channel,
+                            inner: error,
+                        })?;
+
+                if channel_server != server {
+                    return Err(Error::Generic(String::from(
+                        "The channel has to be in this server.",
+                    )));
+                }
+
+                let Some((hour, minute)) = daily::parse_time(time) else {
+                    return Err(Error::InvalidUsage {
+                        message: format!("Unexpected time \"{time}\", expected `HH:MM`."),
+                        usage: self.get_usage(),
+                    });
+                };
+
+                // Moving the puzzle to another channel starts over, answers belong to the old one.
+                let daily = DailyPuzzle {
+                    current: current
+                        .filter(|current| current.channel == channel)
+                        .and_then(|current| current.current),
+                    channel,
+                    hour,
+                    minute,
+                };
+                let reply = format!(
+                    "A puzzle will be posted in <#{}> every day at {} UTC.",
+                    daily.channel,
+                    daily.time()
+                );
+
+                daily::save(&client.cache, &server, daily).map_err(|error| Error::Fetch {
+                    resource: ResourceType::Server,
+                    inner: error,
+                })?;
+
+                reply
+            }
+            Some("stop") => {
+                if current.is_none() {
+                    return Err(Error::Generic(String::from(
+                        "This server has no daily puzzle.",
+                    )));
+                }
+
+                daily::remove(&client.cache, &server).map_err(|error| Error::Fetch {
+                    resource: ResourceType::Server,
+                    inner: error,
+                })?;
+
+                String::from("Daily puzzles are no longer posted.")
+            }
+            Some(action) => {
+                return Err(Error::InvalidUsage {
+                    message: format!("Unexpected argument \"{action}\"."),
+                    usage: self.get_usage(),
+                })
+            }
+            None => current.map_or_else(
+                || String::from("This server has no daily puzzle."),
+                |daily| {
+                    format!(
+                        "A puzzle is posted in <#{}> every day at {} UTC.",
+                        daily.channel,
+                        daily.time()
+                    )
+                },
+            ),
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: true,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct AcceptChallenge;
+
+#[async_trait::async_trait]
+impl Command for AcceptChallenge {
+    fn get_name(&self) -> String {
+        "accept".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[challenger]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        client.games.accept_challenge(client, message).await
+    }
+}
@@ -0,0 +1,23 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct AcceptDraw;
+
+#[async_trait::async_trait]
+impl Command for AcceptDraw {
+    fn get_name(&self) -> String {
+        "accept-draw".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        String::new()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        client.games.accept_draw(client, message).await
+    }
+}
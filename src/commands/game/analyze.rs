@@ -0,0 +1,215 @@
+use chess::Color;
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    commands::{Command, Error},
+    game::{
+        analysis::{self, Judgement},
+        pgn,
+    },
+    render::graph,
+    Client,
+};
+
+use super::replay::find_pgn;
+
+/// Longer move lists are sent as a file instead of a code block.
+const MAX_INLINE_LENGTH: usize = 1000;
+
+/// How many inaccuracies, mistakes and blunders are listed at most.
+const MAX_LISTED_MOVES: usize = 12;
+
+pub struct Analyze;
+
+#[async_trait::async_trait]
+impl Command for Analyze {
+    fn get_name(&self) -> String {
+        "analyze".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["analyse".to_string(), "review".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[game|PGN]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        // Ongoing games are left alone so nobody gets help in the middle of one.
+        let text = find_pgn(client, message, content, false).await?;
+
+        let parsed = pgn::parse(&text).map_err(|error| Error::InvalidUsage {
+            message: error,
+            usage: self.get_usage(),
+        })?;
+
+        if parsed.moves.is_empty() {
+            return Err(Error::Generic(String::from("That game has no moves.")));
+        }
+
+        // A game exported with `pgn` could still be pasted in, so live positions are refused too.
+        if client
+            .games
+            .is_playing(&message.channel, &message.author)
+            .await
+            || client
+                .games
+                .is_live_position(&parsed.final_position())
+                .await
+        {
+            return Err(Error::Generic(String::from(
+                "Games cannot be analysed while they are being played.",
+            )));
+        }
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&format!(
+                        "Analysing {} moves, this takes a moment.",
+                        parsed.moves.len()
+                    ))
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: false,
+                    }]),
+            )
+            .await;
+
+        let analysis = analysis::analyse(&client.engine, &parsed)
+            .await
+            .map_err(|error| Error::Generic(error.to_string()))?;
+
+        let names = [
+            parsed.tag("White").unwrap_or("White"),
+            parsed.tag("Black").unwrap_or("Black"),
+        ];
+
+        let mut lines = vec![format!(
+            "**{} vs {}** {}",
+            names[0],
+            names[1],
+            parsed.tag("Result").unwrap_or("*")
+        )];
+
+        for (color, name) in [Color::White, Color::Black].into_iter().zip(names) {
+            let Some(accuracy) = analysis.accuracy(color) else {
+                continue;
+            };
+
+            lines.push(format!(
+                "{name}: {accuracy:.0}% accuracy, {} inaccuracies, {} mistakes, {} blunders",
+                analysis.count(color, Judgement::Inaccuracy),
+                analysis.count(color, Judgement::Mistake),
+                analysis.count(color, Judgement::Blunder)
+            ));
+        }
+
+        let judged = analysis
+            .moves
+            .iter()
+            .filter_map(|analysed| Some((analysed, analysed.judgement?)))
+            .collect::<Vec<_>>();
+
+        for (analysed, judgement) in judged.iter().take(MAX_LISTED_MOVES) {
+            let dots = match analysed.color {
+                Color::White => ".",
+                Color::Black => "...",
+            };
+
+            lines.push(format!(
+                "{}{dots} {}{} ({}) is {} {judgement}{}",
+                analysed.number,
+                analysed.san,
+                judgement.symbol(),
+                analysed.score,
+                if *judgement == Judgement::Inaccuracy {
+                    "an"
+                } else {
+                    "a"
+                },
+                analysed
+                    .best_move
+                    .as_ref()
+                    .map_or_else(String::new, |best| format!(", {best} was best"))
+            ));
+        }
+
+        if judged.len() > MAX_LISTED_MOVES {
+            lines.push(format!(
+                "…and {} more, see the move list.",
+                judged.len() - MAX_LISTED_MOVES
+            ));
+        }
+
+        let movetext = analysis.movetext();
+        let mut attachments = Vec::new();
+
+        if movetext.len() > MAX_INLINE_LENGTH {
+            match client
+                .upload_attachment("analysis.txt", movetext.into_bytes())
+                .await
+            {
+                Ok(id) => attachments.push(id),
+                Err(error) => {
+                    dbg!(&format!("Failed to upload analysis: {error:?}"));
+                }
+            }
+        } else {
+            lines.push(format!("```\n{movetext}\n```"));
+        }
+
+        let chances = analysis
+            .moves
+            .iter()
+            .map(|analysed| analysed.chances)
+            .collect::<Vec<_>>();
+        let marked = analysis
+            .moves
+            .iter()
+            .enumerate()
+            .filter(|(_, analysed)| {
+                matches!(
+                    analysed.judgement,
+                    Some(Judgement::Mistake | Judgement::Blunder)
+                )
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if let Some(graph) = graph::render(&chances, &marked) {
+            match client.upload_attachment("evaluation.png", graph).await {
+                Ok(id) => attachments.push(id),
+                Err(error) => {
+                    dbg!(&format!("Failed to upload evaluation graph: {error:?}"));
+                }
+            }
+        }
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&lines.join("\n"))
+                    .set_attachments(attachments)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: true,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct DeclineChallenge;
+
+#[async_trait::async_trait]
+impl Command for DeclineChallenge {
+    fn get_name(&self) -> String {
+        "decline".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[challenger]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        client.games.decline_challenge(client, message).await
+    }
+}
@@ -0,0 +1,23 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct DeclineDraw;
+
+#[async_trait::async_trait]
+impl Command for DeclineDraw {
+    fn get_name(&self) -> String {
+        "decline-draw".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        String::new()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        client.games.decline_draw(client, message).await
+    }
+}
@@ -0,0 +1,103 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    commands::{Command, Error},
+    game::archive,
+    Client, ResourceType,
+};
+
+/// How many games are listed at once.
+const LISTED_GAMES: usize = 10;
+
+pub struct Games;
+
+#[async_trait::async_trait]
+impl Command for Games {
+    fn get_name(&self) -> String {
+        "games".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["history".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[user] [vs <user>]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let args = content.split_whitespace().skip(1).collect::<Vec<_>>();
+
+        let (user, opponent) = match args.as_slice() {
+            [] => (None, None),
+            ["vs", opponent] => (None, Some(*opponent)),
+            [user] => (Some(*user), None),
+            [user, "vs", opponent] => (Some(*user), Some(*opponent)),
+            _ => {
+                return Err(Error::InvalidUsage {
+                    message: String::from("Unexpected arguments."),
+                    usage: self.get_usage(),
+                })
+            }
+        };
+
+        let user = match user {
+            Some(user) => resolve(client, user).await?,
+            None => message.author.clone(),
+        };
+        let opponent = match opponent {
+            Some(opponent) => Some(resolve(client, opponent).await?),
+            None => None,
+        };
+
+        let games = archive::recent(&client.cache, &user, opponent.as_deref(), LISTED_GAMES)
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Game,
+                inner: error,
+            })?;
+
+        let content = if games.is_empty() {
+            String::from("No finished games found.")
+        } else {
+            games
+                .iter()
+                .map(archive::ArchivedGame::summary)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: false,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
+
+/// Finds the ID of a mentioned user.
+async fn resolve(client: &Client, text: &str) -> Result<String, Error> {
+    match client.resolve_user(text).await {
+        Ok(Some(user)) => Ok(user.id),
+        Ok(None) => Err(Error::Generic(String::from("Failed to find user."))),
+        Err(error) => Err(Error::Fetch {
+            resource: ResourceType::User,
+            inner: error,
+        }),
+    }
+}
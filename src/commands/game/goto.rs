@@ -0,0 +1,45 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct Goto;
+
+#[async_trait::async_trait]
+impl Command for Goto {
+    fn get_name(&self) -> String {
+        "goto".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "<ply|start|end>".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let target = match content.split_whitespace().nth(1) {
+            Some("start") => Some(0),
+            Some("end") => None,
+            Some(ply) => Some(ply.parse::<usize>().map_err(|_| Error::InvalidUsage {
+                message: format!("`{ply}` is not a ply number."),
+                usage: self.get_usage(),
+            })?),
+            None => {
+                return Err(Error::InvalidUsage {
+                    message: String::from("Which ply do you want to go to?"),
+                    usage: self.get_usage(),
+                })
+            }
+        };
+
+        client
+            .replays
+            .navigate(client, message, |_, total| target.unwrap_or(total))
+            .await
+    }
+}
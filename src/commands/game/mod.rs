@@ -0,0 +1,15 @@
+pub mod accept_challenge;
+pub mod accept_draw;
+pub mod analyze;
+pub mod cancel_challenge;
+pub mod decline_challenge;
+pub mod decline_draw;
+pub mod games;
+pub mod goto;
+pub mod next;
+pub mod offer_draw;
+pub mod pgn;
+pub mod prev;
+pub mod replay;
+pub mod resign;
+pub mod show_game;
@@ -0,0 +1,38 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct Next;
+
+#[async_trait::async_trait]
+impl Command for Next {
+    fn get_name(&self) -> String {
+        "next".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[moves]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let count = match content.split_whitespace().nth(1) {
+            Some(count) => count.parse::<usize>().map_err(|_| Error::InvalidUsage {
+                message: format!("`{count}` is not a number of moves."),
+                usage: self.get_usage(),
+            })?,
+            None => 1,
+        };
+
+        client
+            .replays
+            .navigate(client, message, |ply, _| ply.saturating_add(count))
+            .await
+    }
+}
@@ -0,0 +1,27 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    Client,
+};
+
+pub struct OfferDraw;
+
+#[async_trait::async_trait]
+impl Command for OfferDraw {
+    fn get_name(&self) -> String {
+        "draw".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        String::new()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["offer-draw".to_string()]
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        client.games.offer_draw(client, message).await
+    }
+}
@@ -0,0 +1,77 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    commands::{Command, Error},
+    game::pgn,
+    Client, ResourceType,
+};
+
+/// Longer PGNs are sent as a file instead of a code block.
+const MAX_INLINE_LENGTH: usize = 1900;
+
+pub struct Pgn;
+
+#[async_trait::async_trait]
+impl Command for Pgn {
+    fn get_name(&self) -> String {
+        "pgn".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[game]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mut args = content.split_whitespace();
+
+        if args.next().is_none() {
+            return Ok(());
+        }
+
+        let id = args.next();
+
+        let pgn = match client.games.ongoing_pgn(id, message).await {
+            Some(pgn) => pgn,
+            None => pgn::load(&client.cache, id, &message.author)
+                .map_err(|error| Error::Fetch {
+                    resource: ResourceType::Game,
+                    inner: error,
+                })?
+                .ok_or_else(|| Error::Generic(String::from("Could not find that game.")))?,
+        };
+
+        let (content, attachments) = if pgn.len() > MAX_INLINE_LENGTH {
+            let id = client
+                .upload_attachment("game.pgn", pgn.into_bytes())
+                .await
+                .map_err(|error| Error::Generic(format!("Failed to upload PGN: {error:?}")))?;
+
+            (String::new(), vec![id])
+        } else {
+            (format!("```\n{pgn}```"), Vec::new())
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_attachments(attachments)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: false,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
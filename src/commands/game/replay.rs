@@ -0,0 +1,84 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error},
+    game::pgn,
+    Client, ResourceType,
+};
+
+pub struct Replay;
+
+#[async_trait::async_trait]
+impl Command for Replay {
+    fn get_name(&self) -> String {
+        "replay".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "[game|PGN]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let text = find_pgn(client, message, content, true).await?;
+
+        let parsed = pgn::parse(&text).map_err(|error| Error::InvalidUsage {
+            message: error,
+            usage: self.get_usage(),
+        })?;
+
+        client.replays.start(client, message, &parsed).await
+    }
+}
+
+/// Finds the PGN the command is about: an attached file, a PGN written after the command, or a
+/// game by its ID, defaulting to the author's ongoing or last game.
+///
+/// Games still being played are only found with `include_ongoing`.
+pub(super) async fn find_pgn(
+    client: &Client,
+    message: &Message,
+    content: &str,
+    include_ongoing: bool,
+) -> Result<String, Error> {
+    let rest = content
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, rest)| rest.trim());
+
+    if let Some(attachment) = message
+        .attachments
+        .as_ref()
+        .and_then(|attachments| attachments.first())
+    {
+        return client
+            .download_attachment(&attachment.tag, &attachment.id)
+            .await
+            .map_err(|error| Error::Generic(format!("Failed to download PGN: {error:?}")));
+    }
+
+    if rest.contains(char::is_whitespace) {
+        return Ok(rest.to_string());
+    }
+
+    let id = Some(rest).filter(|rest| !rest.is_empty());
+
+    let ongoing = if include_ongoing {
+        client.games.ongoing_pgn(id, message).await
+    } else {
+        None
+    };
+
+    match ongoing {
+        Some(pgn) => Ok(pgn),
+        None => pgn::load(&client.cache, id, &message.author)
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Game,
+                inner: error,
+            })?
+            .ok_or_else(|| Error::Generic(String::from("Could not find that game."))),
+    }
+}
@@ -0,0 +1,75 @@
+use reywen::structures::channels::message::Message;
+
+use crate::{
+    commands::{Command, Error, PREFIX},
+    game::{archive, send_position},
+    render, Client, ResourceType,
+};
+
+/// Longer move lists would not fit in a message alongside the board.
+const MAX_MOVE_LIST_LENGTH: usize = 1500;
+
+pub struct ShowGame;
+
+#[async_trait::async_trait]
+impl Command for ShowGame {
+    fn get_name(&self) -> String {
+        "game".to_string()
+    }
+
+    fn get_usage(&self) -> String {
+        "<id>".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let Some(id) = content.split_whitespace().nth(1) else {
+            return Err(Error::InvalidUsage {
+                message: String::from("Game ID needed, see `games`."),
+                usage: self.get_usage(),
+            });
+        };
+
+        let archived = archive::load(&client.cache, id)
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Game,
+                inner: error,
+            })?
+            .ok_or_else(|| Error::Generic(String::from("Could not find that game.")))?;
+
+        let Some(board) = archived.final_position() else {
+            return Err(Error::Generic(String::from(
+                "The final position of that game is broken.",
+            )));
+        };
+
+        let moves = archived.move_list();
+        let moves = if moves.len() > MAX_MOVE_LIST_LENGTH {
+            format!("Too many moves to list, use `{PREFIX}pgn {id}` to see them.")
+        } else {
+            moves
+        };
+
+        let render_mode = client
+            .fetch_server_id(&message.channel)
+            .await
+            .and_then(|server| render::server_mode(&client.cache, &server))
+            .unwrap_or_default();
+
+        send_position(
+            client,
+            &message.channel,
+            Some(&message.id),
+            &format!("{}\n{moves}", archived.summary()),
+            &board,
+            archived.last_move(),
+            render_mode,
+        )
+        .await;
+
+        Ok(())
+    }
+}
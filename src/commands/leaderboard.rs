@@ -0,0 +1,121 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{game::stats, Client, ResourceType};
+
+use super::{Command, Error};
+
+const PAGE_SIZE: usize = 10;
+
+#[derive(Default)]
+pub struct Leaderboard;
+
+#[async_trait::async_trait]
+impl Command for Leaderboard {
+    fn get_name(&self) -> String {
+        "leaderboard".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["top".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[global] [page]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mut args = content.split_whitespace().skip(1).peekable();
+
+        let global = args
+            .next_if(|arg| arg.eq_ignore_ascii_case("global"))
+            .is_some();
+
+        let page = match args.next() {
+            Some(page) => match page.parse::<usize>() {
+                Ok(page) if page > 0 => page,
+                _ => {
+                    return Err(Error::InvalidUsage {
+                        message: format!("`{page}` is not a page number."),
+                        usage: self.get_usage(),
+                    })
+                }
+            },
+            None => 1,
+        };
+
+        let Some(offset) = (page - 1).checked_mul(PAGE_SIZE) else {
+            return Err(Error::Generic(format!("There is no page {page}.")));
+        };
+
+        let scope = if global {
+            stats::GLOBAL.to_string()
+        } else {
+            client
+                .fetch_server_id(&message.channel)
+                .await
+                .map_err(|error| Error::Fetch {
+                    resource: ResourceType::Server,
+                    inner: error,
+                })?
+        };
+
+        let (entries, total) = stats::leaderboard(&client.cache, &scope, page - 1, PAGE_SIZE)
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Server,
+                inner: error,
+            })?;
+
+        let pages = total.div_ceil(PAGE_SIZE).max(1);
+        let title = if global {
+            "Global leaderboard"
+        } else {
+            "Leaderboard"
+        };
+
+        let content = if total == 0 {
+            String::from("Nobody has finished a game yet.")
+        } else if entries.is_empty() {
+            format!("There are only {pages} pages.")
+        } else {
+            let mut lines = vec![format!("**{title}** (page {page}/{pages})")];
+
+            for (rank, (player, tally)) in (offset + 1..).zip(entries) {
+                let name = client
+                    .fetch_user(&player)
+                    .await
+                    .map_or(player, |user| user.username);
+
+                lines.push(format!(
+                    "{rank}. {name}: {} points, {} games, {}% won",
+                    tally.score(),
+                    tally.games(),
+                    tally.win_rate()
+                ));
+            }
+
+            lines.join("\n")
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: true,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
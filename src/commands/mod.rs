@@ -1,13 +1,21 @@
-use reywen::structures::channels::message::Message;
+use reywen::structures::channels::{message::Message, Channel};
 use rust_embed::RustEmbed;
 
 use crate::{Client, ResourceType};
 
 mod moderation;
 mod chess;
+mod chess960;
+mod daily_puzzle;
+mod game;
 mod help;
+mod leaderboard;
+mod profile;
+mod puzzle;
+mod render_mode;
+mod rush;
 
-const PREFIX: &str = "!";
+pub const PREFIX: &str = "!";
 
 #[derive(RustEmbed)]
 #[folder = "command-help"]
@@ -65,23 +73,62 @@ pub async fn handle_command(client: &Client, message: &Message) -> Result<(), Er
         return Ok(());
     };
 
-    'outer: for command in COMMANDS {
-        if content.starts_with(&(PREFIX.to_string() + &command.get_name())) {
-            command.execute(client, message).await?;
-            break;
-        }
+    let Some(name) = content
+        .split_whitespace()
+        .next()
+        .and_then(|name| name.strip_prefix(PREFIX))
+    else {
+        return Ok(());
+    };
 
-        for alias in command.get_aliases() {
-            if content.starts_with(&(PREFIX.to_string() + &alias)) {
-                command.execute(client, message).await?;
-                break 'outer;
-            }
-        }
+    if let Some(command) = COMMANDS.iter().find(|command| {
+        command.get_name() == name || command.get_aliases().iter().any(|alias| alias == name)
+    }) {
+        command.execute(client, message).await?;
     }
 
     Ok(())
 }
 
+/// Gets the server the message was sent in, making sure its author owns it before they change
+/// one of its settings. Fails if the owner cannot be confirmed, and outside of servers.
+async fn server_owned_by_author(
+    client: &Client,
+    message: &Message,
+    setting: &str,
+) -> Result<String, Error> {
+    let channel = client
+        .fetch_channel(&message.channel)
+        .await
+        .map_err(|error| Error::Fetch {
+            resource: ResourceType::Channel,
+            inner: error,
+        })?;
+
+    let Channel::TextChannel { server, .. } = channel else {
+        return Err(Error::Generic(format!(
+            "The {setting} can only be changed in servers."
+        )));
+    };
+
+    let owner = client
+        .fetch_server(&server)
+        .await
+        .map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?
+        .owner;
+
+    if owner != message.author {
+        return Err(Error::Generic(format!(
+            "Only the server owner can change the {setting}."
+        )));
+    }
+
+    Ok(server)
+}
+
 pub fn get_help_file(command_name: &str) -> Option<String> {
     let file = CommandHelp::get(&format!("{command_name}.md"))?;
 
@@ -97,4 +144,4 @@ pub fn get_help_file(command_name: &str) -> Option<String> {
     }
 }
 
-const COMMANDS: &[&(dyn Command + Send + Sync)] = &[&chess::Chess, &help::Help, &moderation::ban::Ban, &moderation::kick::Kick, &moderation::unban::Unban];
+const COMMANDS: &[&(dyn Command + Send + Sync)] = &[&chess::Chess, &chess960::Chess960, &game::accept_challenge::AcceptChallenge, &game::decline_challenge::DeclineChallenge, &game::cancel_challenge::CancelChallenge, &game::resign::Resign, &game::offer_draw::OfferDraw, &game::accept_draw::AcceptDraw, &game::decline_draw::DeclineDraw, &game::pgn::Pgn, &game::replay::Replay, &game::next::Next, &game::prev::Prev, &game::goto::Goto, &game::games::Games, &game::show_game::ShowGame, &game::analyze::Analyze, &help::Help, &leaderboard::Leaderboard, &profile::Profile, &puzzle::Puzzle, &rush::Rush, &daily_puzzle::DailyPuzzleCommand, &render_mode::RenderModeCommand, &moderation::ban::Ban, &moderation::kick::Kick, &moderation::unban::Unban];
@@ -0,0 +1,145 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    game::{
+        outcome::color_name,
+        stats::{self, GameResult, Tally},
+    },
+    Client, ResourceType,
+};
+
+use super::{Command, Error};
+
+#[derive(Default)]
+pub struct Profile;
+
+#[async_trait::async_trait]
+impl Command for Profile {
+    fn get_name(&self) -> String {
+        "profile".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["stats".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[user]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let user = match content.split_whitespace().nth(1) {
+            Some(user) => match client.resolve_user(user).await {
+                Ok(Some(user)) => user,
+                Ok(None) => return Err(Error::Generic(String::from("Failed to find user."))),
+                Err(error) => {
+                    return Err(Error::Fetch {
+                        resource: ResourceType::User,
+                        inner: error,
+                    })
+                }
+            },
+            None => client
+                .fetch_user(&message.author)
+                .await
+                .map_err(|error| Error::Fetch {
+                    resource: ResourceType::User,
+                    inner: error,
+                })?,
+        };
+
+        let profile = stats::profile(&client.cache, &user.id).map_err(|error| Error::Fetch {
+            resource: ResourceType::User,
+            inner: error,
+        })?;
+
+        let content = if profile.games() == 0 {
+            format!("{} has not finished any games yet.", user.username)
+        } else {
+            let mut lines = vec![
+                format!("**{}**", user.username),
+                format!("As white: {}", describe_tally(&profile.white)),
+                format!("As black: {}", describe_tally(&profile.black)),
+            ];
+
+            if let Some((result, length)) = profile.streak {
+                lines.push(format!(
+                    "Current streak: {length} {}",
+                    match (result, length) {
+                        (GameResult::Win, 1) => "win",
+                        (GameResult::Win, _) => "wins",
+                        (GameResult::Loss, 1) => "loss",
+                        (GameResult::Loss, _) => "losses",
+                        (GameResult::Draw, 1) => "draw",
+                        (GameResult::Draw, _) => "draws",
+                    }
+                ));
+            }
+
+            let first_moves = profile.favourite_first_moves(3);
+
+            if !first_moves.is_empty() {
+                lines.push(format!(
+                    "Favourite first moves: {}",
+                    first_moves
+                        .iter()
+                        .map(|(san, times)| format!("{san} ({times})"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+
+            if let Some(average) = profile.average_length() {
+                lines.push(format!("Average game length: {average} moves"));
+            }
+
+            let mut recent = Vec::with_capacity(profile.recent.len());
+
+            for game in &profile.recent {
+                let opponent = client
+                    .fetch_user(&game.opponent)
+                    .await
+                    .map_or_else(|_| game.opponent.clone(), |user| user.username);
+
+                recent.push(format!(
+                    "{} vs {opponent} as {}",
+                    game.result.letter(),
+                    color_name(game.color)
+                ));
+            }
+
+            lines.push(format!("Last games: {}", recent.join(", ")));
+
+            lines.join("\n")
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: true,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
+
+fn describe_tally(tally: &Tally) -> String {
+    format!(
+        "{} won, {} lost, {} drawn",
+        tally.wins, tally.losses, tally.draws
+    )
+}
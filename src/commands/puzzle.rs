@@ -0,0 +1,98 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use super::{Command, Error};
+use crate::{puzzle::stats, Client, ResourceType};
+
+pub struct Puzzle;
+
+#[async_trait::async_trait]
+impl Command for Puzzle {
+    fn get_name(&self) -> String {
+        "puzzle".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["tactic".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[theme|stats [user]]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mut args = content.split_whitespace().skip(1);
+
+        match args.next() {
+            Some("stats") => show_stats(client, message, args.next()).await,
+            theme => client.puzzles.start(client, message, theme).await,
+        }
+    }
+}
+
+async fn show_stats(client: &Client, message: &Message, user: Option<&str>) -> Result<(), Error> {
+    let user = match user {
+        Some(user) => match client.resolve_user(user).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(Error::Generic(String::from("Failed to find user."))),
+            Err(error) => {
+                return Err(Error::Fetch {
+                    resource: ResourceType::User,
+                    inner: error,
+                })
+            }
+        },
+        None => client
+            .fetch_user(&message.author)
+            .await
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::User,
+                inner: error,
+            })?,
+    };
+
+    let stats = stats::load(&client.cache, &user.id).map_err(|error| Error::Fetch {
+        resource: ResourceType::User,
+        inner: error,
+    })?;
+
+    let content = if stats.attempts == 0 {
+        format!("{} has not tried any puzzles yet.", user.username)
+    } else {
+        format!(
+            "**{}**\nPuzzle rating: {}\nAttempts: {}, {} solved ({}%)\nRating history: {}",
+            user.username,
+            stats.rating,
+            stats.attempts,
+            stats.solved,
+            stats.solve_rate(),
+            stats
+                .history
+                .iter()
+                .map(|(_, rating)| format!("{rating:.0}"))
+                .collect::<Vec<_>>()
+                .join(" → ")
+        )
+    };
+
+    let _ = client
+        .driver
+        .message_send(
+            &message.channel,
+            &DataMessageSend::new()
+                .set_content(&content)
+                .set_replies(vec![Reply {
+                    id: message.id.clone(),
+                    mention: true,
+                }]),
+        )
+        .await;
+
+    Ok(())
+}
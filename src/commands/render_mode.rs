@@ -0,0 +1,92 @@
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+
+use crate::{
+    render::{self, RenderMode},
+    Client, ResourceType,
+};
+
+use super::{Command, Error};
+
+#[derive(Default)]
+pub struct RenderModeCommand;
+
+#[async_trait::async_trait]
+impl Command for RenderModeCommand {
+    fn get_name(&self) -> String {
+        "render-mode".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["board-style".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[image|text]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mut args = content.split_whitespace();
+
+        if args.next().is_none() {
+            return Ok(());
+        }
+
+        let server = client
+            .fetch_server_id(&message.channel)
+            .await
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Channel,
+                inner: error,
+            })?;
+
+        let content = if let Some(mode) = args.next() {
+            let Ok(mode) = mode.parse::<RenderMode>() else {
+                return Err(Error::InvalidUsage {
+                    message: format!("Unexpected render mode \"{mode}\"."),
+                    usage: self.get_usage(),
+                });
+            };
+
+            super::server_owned_by_author(client, message, "render mode").await?;
+
+            render::set_server_mode(&client.cache, &server, mode).map_err(|error| {
+                Error::Fetch {
+                    resource: ResourceType::Server,
+                    inner: error,
+                }
+            })?;
+
+            format!("New games will now show the board as {mode}.")
+        } else {
+            let mode =
+                render::server_mode(&client.cache, &server).map_err(|error| Error::Fetch {
+                    resource: ResourceType::Server,
+                    inner: error,
+                })?;
+
+            format!("Games show the board as {mode}.")
+        };
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: true,
+                    }]),
+            )
+            .await;
+
+        Ok(())
+    }
+}
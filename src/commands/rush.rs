@@ -0,0 +1,37 @@
+use reywen::structures::channels::message::Message;
+
+use super::{Command, Error};
+use crate::{puzzle::rush::RushMode, Client};
+
+pub struct Rush;
+
+#[async_trait::async_trait]
+impl Command for Rush {
+    fn get_name(&self) -> String {
+        "rush".to_string()
+    }
+
+    fn get_aliases(&self) -> Vec<String> {
+        vec!["puzzle-rush".to_string()]
+    }
+
+    fn get_usage(&self) -> String {
+        "[3m|5m|survival]".to_string()
+    }
+
+    async fn execute(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let Some(content) = &message.content else {
+            return Ok(());
+        };
+
+        let mode = match content.split_whitespace().nth(1) {
+            Some(mode) => mode.parse::<RushMode>().map_err(|()| Error::InvalidUsage {
+                message: format!("Unexpected mode \"{mode}\"."),
+                usage: self.get_usage(),
+            })?,
+            None => RushMode::ThreeMinutes,
+        };
+
+        client.puzzles.start_rush(client, message, mode).await
+    }
+}
@@ -0,0 +1,323 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use chess::{Board, EMPTY};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::game::Game;
+
+pub mod search;
+mod uci;
+
+use uci::UciEngine;
+
+/// The engine binary to play with, set with `UCI_ENGINE`. The built-in engine plays without one.
+static UCI_ENGINE: Lazy<Option<String>> = Lazy::new(|| {
+    std::env::var("UCI_ENGINE")
+        .ok()
+        .filter(|path| !path.is_empty())
+});
+
+/// The strength of the UCI engine from 0 to 20 in games without a difficulty, configurable with
+/// `ENGINE_SKILL_LEVEL`.
+static SKILL_LEVEL: Lazy<u8> = Lazy::new(|| {
+    std::env::var("ENGINE_SKILL_LEVEL")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(20)
+        .min(20)
+});
+
+/// How long the engine thinks about each move, configurable with `ENGINE_THINK_TIME` in milliseconds.
+static THINK_TIME: Lazy<Duration> = Lazy::new(|| {
+    Duration::from_millis(
+        std::env::var("ENGINE_THINK_TIME")
+            .ok()
+            .and_then(|time| time.parse().ok())
+            .unwrap_or(1000),
+    )
+});
+
+/// How long each position is analysed for, configurable with `ANALYSIS_TIME` in milliseconds.
+static ANALYSIS_TIME: Lazy<Duration> = Lazy::new(|| {
+    Duration::from_millis(
+        std::env::var("ANALYSIS_TIME")
+            .ok()
+            .and_then(|time| time.parse().ok())
+            .unwrap_or(300),
+    )
+});
+
+/// How well the bot plays in a game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Strong,
+}
+
+impl Difficulty {
+    /// How many plies deep the built-in engine searches.
+    const fn depth(self) -> u8 {
+        match self {
+            Self::Beginner => 1,
+            Self::Easy => 2,
+            Self::Medium => 3,
+            Self::Hard => 5,
+            Self::Strong => u8::MAX,
+        }
+    }
+
+    /// The most centipawns the built-in engine is willing to give away for a random move.
+    const fn noise(self) -> i32 {
+        match self {
+            Self::Beginner => 300,
+            Self::Easy => 120,
+            Self::Medium => 40,
+            Self::Hard => 10,
+            Self::Strong => 0,
+        }
+    }
+
+    /// The UCI `Skill Level` option from 0 to 20.
+    const fn skill_level(self) -> u8 {
+        match self {
+            Self::Beginner => 0,
+            Self::Easy => 5,
+            Self::Medium => 10,
+            Self::Hard => 15,
+            Self::Strong => 20,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "beginner" => Ok(Self::Beginner),
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            "strong" => Ok(Self::Strong),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Beginner => "beginner",
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Strong => "strong",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum EngineError {
+    Io(std::io::Error),
+    Protocol(String),
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Could not talk to the chess engine: {error}"),
+            Self::Protocol(error) => write!(f, "The chess engine misbehaved: {error}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for EngineError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// How good a position is for the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    /// Moves until checkmate, negative when the side to move gets mated.
+    Mate(i32),
+    /// The side to move is checkmated.
+    Mated,
+    /// The other side is checkmated, which is [`Score::Mated`] seen from the winner's side.
+    Won,
+}
+
+impl Score {
+    /// The score for the other side.
+    #[must_use]
+    pub const fn flip(self) -> Self {
+        match self {
+            Self::Centipawns(centipawns) => Self::Centipawns(-centipawns),
+            Self::Mate(moves) => Self::Mate(-moves),
+            Self::Mated => Self::Won,
+            Self::Won => Self::Mated,
+        }
+    }
+
+    /// The score for a mate in the given number of moves, as engines report it.
+    const fn mate(moves: i32) -> Self {
+        if moves == 0 {
+            Self::Mated
+        } else {
+            Self::Mate(moves)
+        }
+    }
+
+    fn from_search(score: i32) -> Self {
+        if score.abs() >= search::MATE - 100 {
+            let moves = (search::MATE - score.abs() + 1) / 2;
+
+            Self::mate(if score > 0 { moves } else { -moves })
+        } else {
+            Self::Centipawns(score)
+        }
+    }
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Centipawns(centipawns) => write!(f, "{:+.1}", f64::from(*centipawns) / 100.0),
+            Self::Mate(moves) if *moves > 0 => write!(f, "#{moves}"),
+            Self::Mate(moves) => write!(f, "#-{}", moves.unsigned_abs()),
+            Self::Mated => f.write_str("#-0"),
+            Self::Won => f.write_str("#0"),
+        }
+    }
+}
+
+/// The engine's verdict on a position.
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub score: Score,
+    /// The best move in UCI notation, or `None` if the game is over.
+    pub best_move: Option<String>,
+}
+
+/// A position to search, as the starting position and the moves played from it in UCI notation.
+pub struct Position {
+    pub start_fen: Option<String>,
+    pub moves: Vec<String>,
+    pub chess960: bool,
+    /// The current position, for the built-in engine.
+    pub board: Board,
+}
+
+impl From<&Game> for Position {
+    fn from(game: &Game) -> Self {
+        Self {
+            start_fen: game.start_fen.clone(),
+            moves: game
+                .moves
+                .iter()
+                .map(|played| played.chess_move.to_string())
+                .collect(),
+            chess960: game.chess960.is_some(),
+            board: game.game.current_position(),
+        }
+    }
+}
+
+/// Picks the bot's moves, keeping a single engine process alive between them.
+#[derive(Clone, Default)]
+pub struct Engine {
+    uci: Arc<Mutex<Option<UciEngine>>>,
+}
+
+impl Engine {
+    /// Finds the move to play in UCI notation, with the built-in engine if no UCI engine is set.
+    pub async fn best_move(
+        &self,
+        position: &Position,
+        difficulty: Option<Difficulty>,
+    ) -> Result<String, EngineError> {
+        let Some(path) = UCI_ENGINE.as_deref() else {
+            let board = position.board;
+            let difficulty = difficulty.unwrap_or_default();
+
+            return tokio::task::spawn_blocking(move || {
+                search::best_move(&board, difficulty.depth(), *THINK_TIME, difficulty.noise())
+            })
+            .await
+            .map_err(|error| EngineError::Protocol(error.to_string()))?
+            .map(|chess_move| chess_move.to_string())
+            .ok_or_else(|| EngineError::Protocol(String::from("the engine found no move")));
+        };
+
+        let mut uci = self.uci.lock().await;
+
+        let engine = match &mut *uci {
+            Some(engine) => engine,
+            slot @ None => slot.insert(UciEngine::spawn(path).await?),
+        };
+
+        let result = engine
+            .best_move(
+                position,
+                difficulty.map_or(*SKILL_LEVEL, Difficulty::skill_level),
+                *THINK_TIME,
+            )
+            .await;
+
+        // A broken engine is restarted on the next move.
+        if result.is_err() {
+            *uci = None;
+        }
+
+        result
+    }
+
+    /// Evaluates a position at full strength, with the built-in engine if no UCI engine is set.
+    pub async fn evaluate(&self, position: &Position) -> Result<Evaluation, EngineError> {
+        let Some(path) = UCI_ENGINE.as_deref() else {
+            let board = position.board;
+
+            return tokio::task::spawn_blocking(move || {
+                match search::search(&board, u8::MAX, *ANALYSIS_TIME, 0).first() {
+                    Some((chess_move, score)) => Evaluation {
+                        score: Score::from_search(*score),
+                        best_move: Some(chess_move.to_string()),
+                    },
+                    None if *board.checkers() == EMPTY => Evaluation {
+                        score: Score::Centipawns(0),
+                        best_move: None,
+                    },
+                    None => Evaluation {
+                        score: Score::Mated,
+                        best_move: None,
+                    },
+                }
+            })
+            .await
+            .map_err(|error| EngineError::Protocol(error.to_string()));
+        };
+
+        let mut uci = self.uci.lock().await;
+
+        let engine = match &mut *uci {
+            Some(engine) => engine,
+            slot @ None => slot.insert(UciEngine::spawn(path).await?),
+        };
+
+        let result = engine.evaluate(position, *ANALYSIS_TIME).await;
+
+        if result.is_err() {
+            *uci = None;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use chess::{Board, BoardStatus, ChessMove, Color, MoveGen, Piece, EMPTY};
+use rand::Rng;
+
+/// The score of a checkmate, less the number of plies it takes.
+pub const MATE: i32 = 30_000;
+const INFINITY: i32 = 32_000;
+/// The transposition table is cleared once it holds this many positions.
+const TABLE_SIZE: usize = 1 << 20;
+/// Quiescence search stops following checks this many plies deep.
+const MAX_PLY: i32 = 64;
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+const fn piece_value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 100,
+        Piece::Knight => 320,
+        Piece::Bishop => 330,
+        Piece::Rook => 500,
+        Piece::Queen => 900,
+        Piece::King => 0,
+    }
+}
+
+/// Scores the position in centipawns from the side to move's point of view.
+pub fn evaluate(board: &Board) -> i32 {
+    // The king walks to the center once little material is left to attack it with.
+    let is_endgame = [Color::White, Color::Black].into_iter().all(|color| {
+        [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen]
+            .into_iter()
+            .map(|piece| {
+                piece_value(piece)
+                    * i32::try_from((*board.pieces(piece) & *board.color_combined(color)).popcnt())
+                        .unwrap_or_default()
+            })
+            .sum::<i32>()
+            <= 1300
+    });
+
+    let score = [Color::White, Color::Black]
+        .into_iter()
+        .map(|color| {
+            let sign = if color == Color::White { 1 } else { -1 };
+
+            [
+                Piece::Pawn,
+                Piece::Knight,
+                Piece::Bishop,
+                Piece::Rook,
+                Piece::Queen,
+                Piece::King,
+            ]
+            .into_iter()
+            .map(|piece| {
+                let table = match piece {
+                    Piece::Pawn => &PAWN_TABLE,
+                    Piece::Knight => &KNIGHT_TABLE,
+                    Piece::Bishop => &BISHOP_TABLE,
+                    Piece::Rook => &ROOK_TABLE,
+                    Piece::Queen => &QUEEN_TABLE,
+                    Piece::King if is_endgame => &KING_ENDGAME_TABLE,
+                    Piece::King => &KING_TABLE,
+                };
+
+                (*board.pieces(piece) & *board.color_combined(color))
+                    .map(|square| {
+                        // The tables are written from white's side with the 8th rank first.
+                        let rank = square.get_rank().to_index();
+                        let rank = if color == Color::White {
+                            7 - rank
+                        } else {
+                            rank
+                        };
+
+                        piece_value(piece) + table[rank * 8 + square.get_file().to_index()]
+                    })
+                    .sum::<i32>()
+            })
+            .sum::<i32>()
+                * sign
+        })
+        .sum::<i32>();
+
+    if board.side_to_move() == Color::White {
+        score
+    } else {
+        -score
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best_move: Option<ChessMove>,
+}
+
+/// An alpha-beta search with quiescence and a transposition table.
+struct Searcher {
+    table: HashMap<u64, Entry>,
+    deadline: Instant,
+    nodes: u64,
+    stopped: bool,
+}
+
+impl Searcher {
+    fn check_time(&mut self) {
+        self.nodes += 1;
+
+        if self.nodes % 1024 == 0 && Instant::now() >= self.deadline {
+            self.stopped = true;
+        }
+    }
+
+    fn negamax(&mut self, board: &Board, depth: u8, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        self.check_time();
+
+        if self.stopped {
+            return 0;
+        }
+
+        match board.status() {
+            BoardStatus::Checkmate => return -MATE + ply,
+            BoardStatus::Stalemate => return 0,
+            BoardStatus::Ongoing => {}
+        }
+
+        if depth == 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        let hash = board.get_hash();
+        let mut table_move = None;
+
+        if let Some(entry) = self.table.get(&hash) {
+            table_move = entry.best_move;
+
+            if entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower if entry.score >= beta => return entry.score,
+                    Bound::Upper if entry.score <= alpha => return entry.score,
+                    _ => {}
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+
+        for chess_move in ordered_moves(board, table_move) {
+            let score = -self.negamax(
+                &board.make_move_new(chess_move),
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+            );
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move);
+            }
+
+            alpha = alpha.max(score);
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+
+        if self.table.len() >= TABLE_SIZE {
+            self.table.clear();
+        }
+
+        self.table.insert(
+            hash,
+            Entry {
+                depth,
+                score: best_score,
+                bound,
+                best_move,
+            },
+        );
+
+        best_score
+    }
+
+    /// Searches captures until the position is quiet, and every reply when in check.
+    fn quiescence(&mut self, board: &Board, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        self.check_time();
+
+        if self.stopped {
+            return 0;
+        }
+
+        if ply >= MAX_PLY {
+            return evaluate(board);
+        }
+
+        let in_check = *board.checkers() != EMPTY;
+
+        if !in_check {
+            let stand_pat = evaluate(board);
+
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+
+            alpha = alpha.max(stand_pat);
+        }
+
+        let mut moves = MoveGen::new_legal(board);
+
+        if !in_check {
+            moves.set_iterator_mask(*board.color_combined(!board.side_to_move()));
+        }
+
+        let mut moves = moves.collect::<Vec<_>>();
+
+        if in_check && moves.is_empty() {
+            return -MATE + ply;
+        }
+
+        moves.sort_by_cached_key(|chess_move| move_order(board, *chess_move));
+
+        for chess_move in moves {
+            let score = -self.quiescence(&board.make_move_new(chess_move), ply + 1, -beta, -alpha);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return score;
+            }
+
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+}
+
+/// Sorts captures of valuable pieces by cheap ones first, after the move from the table.
+fn ordered_moves(board: &Board, table_move: Option<ChessMove>) -> Vec<ChessMove> {
+    let mut moves = MoveGen::new_legal(board).collect::<Vec<_>>();
+
+    moves.sort_by_cached_key(|chess_move| {
+        if Some(*chess_move) == table_move {
+            i32::MIN
+        } else {
+            move_order(board, *chess_move)
+        }
+    });
+
+    moves
+}
+
+fn move_order(board: &Board, chess_move: ChessMove) -> i32 {
+    let promotion = chess_move.get_promotion().map_or(0, piece_value);
+
+    match board.piece_on(chess_move.get_dest()) {
+        Some(victim) => {
+            board
+                .piece_on(chess_move.get_source())
+                .map_or(0, piece_value)
+                / 10
+                - piece_value(victim)
+                - promotion
+        }
+        None => -promotion,
+    }
+}
+
+/// Scores every legal move with iterative deepening, best first.
+///
+/// Moves scoring more than `margin` below the best one are only known to be worse than that.
+pub fn search(board: &Board, max_depth: u8, time: Duration, margin: i32) -> Vec<(ChessMove, i32)> {
+    let mut searcher = Searcher {
+        table: HashMap::new(),
+        deadline: Instant::now() + time,
+        nodes: 0,
+        stopped: false,
+    };
+    let mut moves = MoveGen::new_legal(board)
+        .map(|chess_move| (chess_move, 0))
+        .collect::<Vec<_>>();
+
+    for depth in 1..=max_depth {
+        let mut scored = Vec::with_capacity(moves.len());
+        let mut best = -INFINITY;
+
+        for (chess_move, _) in &moves {
+            let alpha = best.saturating_sub(margin + 1).max(-INFINITY);
+            let score = -searcher.negamax(
+                &board.make_move_new(*chess_move),
+                depth - 1,
+                1,
+                -INFINITY,
+                -alpha,
+            );
+
+            if searcher.stopped {
+                break;
+            }
+
+            best = best.max(score);
+            scored.push((*chess_move, score));
+        }
+
+        // An unfinished iteration is thrown away, unless it is all there is.
+        if searcher.stopped {
+            if depth == 1 && !scored.is_empty() {
+                scored.sort_by_key(|(_, score)| -score);
+                moves = scored;
+            }
+
+            break;
+        }
+
+        scored.sort_by_key(|(_, score)| -score);
+        moves = scored;
+
+        if moves
+            .first()
+            .is_some_and(|(_, score)| score.abs() >= MATE - 100)
+        {
+            break;
+        }
+    }
+
+    moves
+}
+
+/// Picks a move, adding up to `noise` centipawns of randomness to each move's score.
+pub fn best_move(board: &Board, max_depth: u8, time: Duration, noise: i32) -> Option<ChessMove> {
+    let mut rng = rand::thread_rng();
+
+    search(board, max_depth, time, noise)
+        .into_iter()
+        .max_by_key(|(_, score)| score + rng.gen_range(0..=noise))
+        .map(|(chess_move, _)| chess_move)
+}
@@ -0,0 +1,188 @@
+use std::{process::Stdio, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    process::{Child, ChildStdin, ChildStdout, Command},
+};
+
+use super::{EngineError, Evaluation, Position, Score};
+
+/// How long the engine may take to answer beyond the time it was given to think.
+const RESPONSE_GRACE: Duration = Duration::from_secs(10);
+
+/// A chess engine running as a subprocess, spoken to over UCI.
+pub struct UciEngine {
+    // Kept so the process is killed when the engine is dropped.
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    chess960: bool,
+    skill_level: Option<u8>,
+}
+
+impl UciEngine {
+    pub async fn spawn(path: &str) -> Result<Self, EngineError> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(EngineError::Protocol(String::from(
+                "the engine's input and output could not be opened",
+            )));
+        };
+
+        let mut engine = Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            chess960: false,
+            skill_level: None,
+        };
+
+        engine.send("uci").await?;
+        engine.read_until("uciok", RESPONSE_GRACE).await?;
+
+        Ok(engine)
+    }
+
+    async fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        self.stdin
+            .write_all(format!("{command}\n").as_bytes())
+            .await?;
+        self.stdin.flush().await?;
+
+        Ok(())
+    }
+
+    /// Reads lines until one starts with the given token, returning that line.
+    async fn read_until(&mut self, token: &str, timeout: Duration) -> Result<String, EngineError> {
+        tokio::time::timeout(timeout, async {
+            while let Some(line) = self.stdout.next_line().await? {
+                if line.split_whitespace().next() == Some(token) {
+                    return Ok(line);
+                }
+            }
+
+            Err(EngineError::Protocol(String::from("the engine quit")))
+        })
+        .await
+        .map_err(|_| EngineError::Protocol(format!("timed out waiting for `{token}`")))?
+    }
+
+    /// Sets up the position, telling the engine whether castling is written as Chess960.
+    async fn set_position(&mut self, position: &Position) -> Result<(), EngineError> {
+        if self.chess960 != position.chess960 {
+            self.send(&format!(
+                "setoption name UCI_Chess960 value {}",
+                position.chess960
+            ))
+            .await?;
+            self.chess960 = position.chess960;
+        }
+
+        self.send("isready").await?;
+        self.read_until("readyok", RESPONSE_GRACE).await?;
+
+        let mut command = match &position.start_fen {
+            Some(fen) => format!("position fen {fen}"),
+            None => String::from("position startpos"),
+        };
+
+        if !position.moves.is_empty() {
+            command.push_str(" moves ");
+            command.push_str(&position.moves.join(" "));
+        }
+
+        self.send(&command).await
+    }
+
+    /// Thinks about the position for the given time, returning the best move in UCI notation.
+    pub async fn best_move(
+        &mut self,
+        position: &Position,
+        skill_level: u8,
+        think_time: Duration,
+    ) -> Result<String, EngineError> {
+        if self.skill_level != Some(skill_level) {
+            self.send(&format!("setoption name Skill Level value {skill_level}"))
+                .await?;
+            self.skill_level = Some(skill_level);
+        }
+
+        self.set_position(position).await?;
+        self.send(&format!("go movetime {}", think_time.as_millis()))
+            .await?;
+
+        let line = self
+            .read_until("bestmove", think_time + RESPONSE_GRACE)
+            .await?;
+
+        match line.split_whitespace().nth(1) {
+            Some("(none)") | None => Err(EngineError::Protocol(String::from(
+                "the engine found no move",
+            ))),
+            Some(chess_move) => Ok(chess_move.to_string()),
+        }
+    }
+
+    /// Analyses the position at full strength for the given time.
+    pub async fn evaluate(
+        &mut self,
+        position: &Position,
+        think_time: Duration,
+    ) -> Result<Evaluation, EngineError> {
+        if self.skill_level != Some(20) {
+            self.send("setoption name Skill Level value 20").await?;
+            self.skill_level = Some(20);
+        }
+
+        self.set_position(position).await?;
+        self.send(&format!("go movetime {}", think_time.as_millis()))
+            .await?;
+
+        tokio::time::timeout(think_time + RESPONSE_GRACE, async {
+            let mut score = None;
+
+            while let Some(line) = self.stdout.next_line().await? {
+                let mut tokens = line.split_whitespace();
+
+                match tokens.next() {
+                    Some("info") => {
+                        // Bounds from an unfinished search are not real scores.
+                        if line.contains("lowerbound") || line.contains("upperbound") {
+                            continue;
+                        }
+
+                        let mut tokens = tokens.skip_while(|token| *token != "score").skip(1);
+
+                        score = match (tokens.next(), tokens.next().map(str::parse)) {
+                            (Some("cp"), Some(Ok(centipawns))) => {
+                                Some(Score::Centipawns(centipawns))
+                            }
+                            (Some("mate"), Some(Ok(moves))) => Some(Score::mate(moves)),
+                            _ => score,
+                        };
+                    }
+                    Some("bestmove") => {
+                        return Ok(Evaluation {
+                            score: score.unwrap_or(Score::Centipawns(0)),
+                            best_move: tokens
+                                .next()
+                                .filter(|chess_move| *chess_move != "(none)")
+                                .map(String::from),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            Err(EngineError::Protocol(String::from("the engine quit")))
+        })
+        .await
+        .map_err(|_| EngineError::Protocol(String::from("timed out waiting for `bestmove`")))?
+    }
+}
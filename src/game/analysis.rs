@@ -0,0 +1,284 @@
+use chess::{Board, ChessMove, Color};
+
+use crate::engine::{self, Engine, EngineError, Evaluation, Score};
+
+use super::{
+    chess960::Chess960,
+    pgn::{self, ParsedPgn},
+    san,
+};
+
+/// Centipawns standing in for a forced mate when working out winning chances.
+const MATE_CENTIPAWNS: i32 = 10_000;
+
+/// How bad a move was, judged by how much it lowered the mover's winning chances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// Judges a drop in winning chances between -1 and 1, like Lichess does.
+    fn of(drop: f64) -> Option<Self> {
+        if drop >= 0.3 {
+            Some(Self::Blunder)
+        } else if drop >= 0.2 {
+            Some(Self::Mistake)
+        } else if drop >= 0.1 {
+            Some(Self::Inaccuracy)
+        } else {
+            None
+        }
+    }
+
+    /// The annotation symbol added to the move.
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Inaccuracy => "?!",
+            Self::Mistake => "?",
+            Self::Blunder => "??",
+        }
+    }
+}
+
+impl std::fmt::Display for Judgement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Inaccuracy => "inaccuracy",
+            Self::Mistake => "mistake",
+            Self::Blunder => "blunder",
+        })
+    }
+}
+
+pub struct AnalysedMove {
+    pub color: Color,
+    /// The move number as written before white's moves.
+    pub number: u32,
+    pub san: String,
+    /// The evaluation after the move from white's point of view.
+    pub score: Score,
+    /// White's winning chances after the move, between -1 and 1.
+    pub chances: f64,
+    /// How accurate the move was in percent.
+    pub accuracy: f64,
+    pub judgement: Option<Judgement>,
+    /// The engine's choice in algebraic notation, if it differs from the move played.
+    pub best_move: Option<String>,
+}
+
+pub struct Analysis {
+    pub moves: Vec<AnalysedMove>,
+}
+
+impl Analysis {
+    /// The average accuracy of a player's moves in percent.
+    pub fn accuracy(&self, color: Color) -> Option<f64> {
+        let accuracies = self
+            .moves
+            .iter()
+            .filter(|analysed| analysed.color == color)
+            .map(|analysed| analysed.accuracy)
+            .collect::<Vec<_>>();
+
+        let count = u32::try_from(accuracies.len())
+            .ok()
+            .filter(|count| *count > 0)?;
+
+        Some(accuracies.iter().sum::<f64>() / f64::from(count))
+    }
+
+    pub fn count(&self, color: Color, judgement: Judgement) -> usize {
+        self.moves
+            .iter()
+            .filter(|analysed| analysed.color == color && analysed.judgement == Some(judgement))
+            .count()
+    }
+
+    /// The moves with their evaluations and annotations, numbered like in PGN.
+    pub fn movetext(&self) -> String {
+        let mut tokens = Vec::new();
+
+        for (ply, analysed) in self.moves.iter().enumerate() {
+            if analysed.color == Color::White {
+                tokens.push(format!("{}.", analysed.number));
+            } else if ply == 0 {
+                tokens.push(format!("{}...", analysed.number));
+            }
+
+            tokens.push(format!(
+                "{}{} {{{}}}",
+                analysed.san,
+                analysed.judgement.map_or("", Judgement::symbol),
+                analysed.score
+            ));
+        }
+
+        tokens.join(" ")
+    }
+}
+
+/// White's winning chances between -1 and 1, using the curve Lichess fitted to its games.
+fn winning_chances(score: Score) -> f64 {
+    let centipawns = match score {
+        Score::Centipawns(centipawns) => centipawns.clamp(-MATE_CENTIPAWNS, MATE_CENTIPAWNS),
+        Score::Mate(moves) if moves > 0 => MATE_CENTIPAWNS,
+        Score::Mate(_) => -MATE_CENTIPAWNS,
+        Score::Won => return 1.0,
+        Score::Mated => return -1.0,
+    };
+
+    2.0 / (1.0 + (-0.003_682_08 * f64::from(centipawns)).exp()) - 1.0
+}
+
+/// The accuracy of a move from the mover's winning chances before and after it, in percent.
+fn move_accuracy(before: f64, after: f64) -> f64 {
+    // Lichess' formula works with win percentages from 0 to 100.
+    let drop = (before - after).max(0.0) * 50.0;
+
+    103.166_8f64
+        .mul_add((-0.043_54 * drop).exp(), -3.166_9)
+        .clamp(0.0, 100.0)
+}
+
+/// Runs every position of the game through the engine.
+pub async fn analyse(engine: &Engine, parsed: &ParsedPgn) -> Result<Analysis, EngineError> {
+    let mut boards = vec![(parsed.start, parsed.chess960)];
+
+    for chess_move in &parsed.moves {
+        let (board, mut chess960) = boards[boards.len() - 1];
+        let after = match &mut chess960 {
+            Some(chess960) => chess960.make_move(&board, *chess_move),
+            None => board.make_move_new(*chess_move),
+        };
+
+        boards.push((after, chess960));
+    }
+
+    let start_fen = parsed.tag("FEN").map(String::from);
+    let mut evaluations = Vec::with_capacity(boards.len());
+
+    for (ply, (board, _)) in boards.iter().enumerate() {
+        let position = engine::Position {
+            start_fen: start_fen.clone(),
+            moves: parsed.moves[..ply]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            chess960: parsed.chess960.is_some(),
+            board: *board,
+        };
+
+        let evaluation = engine.evaluate(&position).await?;
+
+        // Scores are turned around to be from white's point of view.
+        evaluations.push(Evaluation {
+            score: match board.side_to_move() {
+                Color::White => evaluation.score,
+                Color::Black => evaluation.score.flip(),
+            },
+            ..evaluation
+        });
+    }
+
+    let mut moves = Vec::with_capacity(parsed.moves.len());
+    let (_, mut number) = pgn::first_move(parsed.tag("FEN"), parsed.chess960.is_some());
+
+    for (ply, chess_move) in parsed.moves.iter().enumerate() {
+        let (board, chess960) = boards[ply];
+        let color = board.side_to_move();
+        let before = &evaluations[ply];
+        let after = &evaluations[ply + 1];
+
+        // Chances from the mover's point of view.
+        let sign = match color {
+            Color::White => 1.0,
+            Color::Black => -1.0,
+        };
+        let chances_before = sign * winning_chances(before.score);
+        let chances_after = sign * winning_chances(after.score);
+
+        let is_best = before.best_move.as_deref() == Some(&chess_move.to_string());
+        let judgement = if is_best {
+            None
+        } else {
+            Judgement::of(chances_before - chances_after)
+        };
+
+        moves.push(AnalysedMove {
+            color,
+            number,
+            san: format_move(&board, chess960, *chess_move),
+            score: after.score,
+            chances: winning_chances(after.score),
+            accuracy: if is_best {
+                100.0
+            } else {
+                move_accuracy(chances_before, chances_after)
+            },
+            judgement,
+            best_move: before
+                .best_move
+                .as_deref()
+                .filter(|_| !is_best)
+                .and_then(|best| parse_uci(&board, chess960, best))
+                .map(|best| format_move(&board, chess960, best)),
+        });
+
+        if color == Color::Black {
+            number += 1;
+        }
+    }
+
+    Ok(Analysis { moves })
+}
+
+fn parse_uci(board: &Board, chess960: Option<Chess960>, text: &str) -> Option<ChessMove> {
+    chess960
+        .and_then(|chess960| chess960.parse(board, text))
+        .unwrap_or_else(|| san::parse_uci(board, text))
+        .ok()
+}
+
+fn format_move(board: &Board, chess960: Option<Chess960>, chess_move: ChessMove) -> String {
+    chess960.map_or_else(
+        || san::format(board, chess_move),
+        |chess960| chess960.format(board, chess_move),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mate_is_a_win_for_the_mating_side() {
+        assert_eq!(Score::Mated.flip(), Score::Won);
+        assert_eq!(Score::Won.flip(), Score::Mated);
+        assert!((winning_chances(Score::Won) - 1.0).abs() < f64::EPSILON);
+        assert!((winning_chances(Score::Mated) + 1.0).abs() < f64::EPSILON);
+
+        // Mating with another move than the engine's is still perfect.
+        assert!(move_accuracy(0.6, winning_chances(Score::Won)) > 99.9);
+    }
+
+    #[test]
+    fn game_ending_in_checkmate() {
+        let parsed = pgn::parse("1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0").unwrap();
+        let analysis = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(analyse(&Engine::default(), &parsed))
+            .unwrap();
+
+        let mate = analysis.moves.last().unwrap();
+
+        assert_eq!(mate.color, Color::White);
+        assert_eq!(mate.score, Score::Won);
+        assert_eq!(mate.score.to_string(), "#0");
+        assert!((mate.chances - 1.0).abs() < f64::EPSILON);
+        assert_eq!(mate.judgement, None);
+    }
+}
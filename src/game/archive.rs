@@ -0,0 +1,200 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chess::{Board, ChessMove, Color};
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+use crate::RedisArchivedGame;
+
+use super::{
+    clock::TimeControl,
+    outcome::{Outcome, Termination},
+    pgn, san, Game,
+};
+
+const ARCHIVE_KEY: &str = "archived-games";
+
+/// How many of a user's games are looked through when only games against one opponent are wanted.
+const SEARCH_LIMIT: isize = 500;
+
+/// A finished game, kept under the ID of the message that started it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedGame {
+    pub id: String,
+    pub server: String,
+    pub channel: String,
+    pub white: String,
+    pub black: String,
+    pub white_name: String,
+    pub black_name: String,
+    /// The result as written in PGN.
+    pub result: String,
+    pub termination: Termination,
+    pub start_fen: Option<String>,
+    pub chess960: bool,
+    /// The moves in UCI notation.
+    pub moves: Vec<String>,
+    /// The moves in algebraic notation.
+    pub sans: Vec<String>,
+    pub final_fen: String,
+    pub time_control: Option<TimeControl>,
+    pub rated: bool,
+    pub started_at: SystemTime,
+    pub ended_at: SystemTime,
+}
+
+impl ArchivedGame {
+    fn new(game: &Game, outcome: Outcome) -> Self {
+        Self {
+            id: game.id.clone(),
+            server: game.server.clone(),
+            channel: game.channel.clone(),
+            white: game.white.id.clone(),
+            black: game.black.id.clone(),
+            white_name: game.white.username.clone(),
+            black_name: game.black.username.clone(),
+            result: outcome.result().to_string(),
+            termination: outcome.termination,
+            start_fen: game.start_fen.clone(),
+            chess960: game.chess960.is_some(),
+            moves: game
+                .moves
+                .iter()
+                .map(|played| played.chess_move.to_string())
+                .collect(),
+            sans: game.moves.iter().map(|played| played.san.clone()).collect(),
+            final_fen: game.game.current_position().to_string(),
+            time_control: game.clock.as_ref().map(|clock| clock.control),
+            rated: game.rated,
+            started_at: game.started_at,
+            ended_at: SystemTime::now(),
+        }
+    }
+
+    pub fn final_position(&self) -> Option<Board> {
+        Board::from_str(&self.final_fen).ok()
+    }
+
+    pub fn last_move(&self) -> Option<ChessMove> {
+        self.moves
+            .last()
+            .and_then(|text| san::parse_uci_syntax(text))
+    }
+
+    /// The moves in algebraic notation with their move numbers.
+    pub fn move_list(&self) -> String {
+        let (mut color, mut number) = pgn::first_move(self.start_fen.as_deref(), self.chess960);
+        let mut tokens = Vec::new();
+
+        for (ply, san) in self.sans.iter().enumerate() {
+            if color == Color::White {
+                tokens.push(format!("{number}."));
+            } else if ply == 0 {
+                tokens.push(format!("{number}..."));
+            }
+
+            tokens.push(san.clone());
+
+            if color == Color::Black {
+                number += 1;
+            }
+
+            color = !color;
+        }
+
+        tokens.join(" ")
+    }
+
+    /// A one line description with the ID, players, result and date.
+    pub fn summary(&self) -> String {
+        format!(
+            "`{}` {} vs {}, {} by {} on {}",
+            self.id,
+            self.white_name,
+            self.black_name,
+            self.result,
+            self.termination,
+            pgn::format_date(self.ended_at)
+        )
+    }
+}
+
+fn user_key(user_id: &str) -> String {
+    format!("archived-games:{user_id}")
+}
+
+/// Archives a finished game, indexing it by both players.
+pub fn store(cache: &redis::Client, game: &Game, outcome: Outcome) {
+    let archived = ArchivedGame::new(game, outcome);
+    let ended_at = archived
+        .ended_at
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    let result = cache.get_connection().and_then(|mut conn| {
+        conn.hset::<_, _, _, ()>(ARCHIVE_KEY, &game.id, RedisArchivedGame(archived))?;
+
+        for player in [&game.white.id, &game.black.id] {
+            conn.zadd::<_, _, _, ()>(user_key(player), &game.id, ended_at)?;
+        }
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        dbg!(&format!("Failed to archive game {}: {error}", game.id));
+    }
+}
+
+pub fn load(cache: &redis::Client, id: &str) -> crate::Result<Option<ArchivedGame>> {
+    let mut conn = cache.get_connection()?;
+    let archived: Option<RedisArchivedGame> = conn.hget(ARCHIVE_KEY, id)?;
+
+    Ok(archived.map(|RedisArchivedGame(archived)| archived))
+}
+
+/// The user's latest games, newest first, optionally only those against one opponent.
+pub fn recent(
+    cache: &redis::Client,
+    user_id: &str,
+    opponent: Option<&str>,
+    count: usize,
+) -> crate::Result<Vec<ArchivedGame>> {
+    let mut conn = cache.get_connection()?;
+
+    let ids: Vec<String> = conn.zrevrange(
+        user_key(user_id),
+        0,
+        if opponent.is_some() {
+            SEARCH_LIMIT - 1
+        } else {
+            isize::try_from(count).unwrap_or(SEARCH_LIMIT) - 1
+        },
+    )?;
+
+    let mut games = Vec::with_capacity(count);
+
+    for id in ids {
+        let Some(RedisArchivedGame(archived)) =
+            conn.hget::<_, _, Option<RedisArchivedGame>>(ARCHIVE_KEY, &id)?
+        else {
+            continue;
+        };
+
+        if opponent.is_none_or(|opponent| {
+            archived.white == opponent && archived.black == user_id
+                || archived.black == opponent && archived.white == user_id
+        }) {
+            games.push(archived);
+        }
+
+        if games.len() == count {
+            break;
+        }
+    }
+
+    Ok(games)
+}
@@ -0,0 +1,223 @@
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use reywen::structures::{channels::message::Message, users::User};
+
+use crate::{
+    commands::{Error, PREFIX},
+    Client, ResourceType,
+};
+
+use super::{outcome::color_name, send_reply, Game, GameManager};
+
+/// How long an opponent has to answer a challenge, configurable with `CHALLENGE_TIMEOUT` in seconds.
+static CHALLENGE_TIMEOUT: Lazy<Duration> = Lazy::new(|| {
+    Duration::from_secs(
+        std::env::var("CHALLENGE_TIMEOUT")
+            .ok()
+            .and_then(|timeout| timeout.parse().ok())
+            .unwrap_or(120),
+    )
+});
+
+/// A game waiting for the opponent to accept it.
+pub struct Challenge {
+    pub game: Game,
+    pub challenger: String,
+    pub expires_at: Instant,
+}
+
+impl Challenge {
+    pub fn opponent(&self) -> &User {
+        if self.game.white.id == self.challenger {
+            &self.game.black
+        } else {
+            &self.game.white
+        }
+    }
+}
+
+impl GameManager {
+    /// Invites the opponent to the game, starting it right away if someone challenges themselves
+    /// or the bot.
+    pub async fn challenge(
+        &self,
+        client: &Client,
+        message: &Message,
+        game: Game,
+    ) -> Result<(), Error> {
+        if game.white.id == game.black.id || game.is_player(&client.user.id) {
+            return self.begin(client, game, &message.id).await;
+        }
+
+        let mut challenges = self.challenges.lock().await;
+
+        if challenges.values().any(|challenge| {
+            challenge.game.channel == message.channel && challenge.challenger == message.author
+        }) {
+            return Err(Error::Generic(format!(
+                "You already have a pending challenge in this channel, use `{PREFIX}cancel` to withdraw it."
+            )));
+        }
+
+        let challenge = Challenge {
+            game,
+            challenger: message.author.clone(),
+            expires_at: Instant::now() + *CHALLENGE_TIMEOUT,
+        };
+
+        let content = format!(
+            "<@{}>, <@{}> challenges you to a game of chess playing {}. Answer with `{PREFIX}accept` or `{PREFIX}decline` within {} seconds.",
+            challenge.opponent().id,
+            challenge.challenger,
+            challenge
+                .game
+                .color_of(&challenge.challenger)
+                .map_or("random", color_name),
+            CHALLENGE_TIMEOUT.as_secs()
+        );
+
+        challenges.insert(challenge.game.id.clone(), challenge);
+        drop(challenges);
+
+        send_reply(client, &message.channel, &message.id, &content).await;
+
+        Ok(())
+    }
+
+    pub async fn accept_challenge(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let challenge = self.take_challenge(client, message).await?;
+
+        self.begin(client, challenge.game, &message.id).await
+    }
+
+    pub async fn decline_challenge(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let challenge = self.take_challenge(client, message).await?;
+
+        send_reply(
+            client,
+            &message.channel,
+            &message.id,
+            &format!(
+                "<@{}>, <@{}> declined your challenge.",
+                challenge.challenger, message.author
+            ),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    pub async fn cancel_challenge(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let mut challenges = self.challenges.lock().await;
+
+        let Some(id) = challenges
+            .values()
+            .find(|challenge| {
+                challenge.game.channel == message.channel && challenge.challenger == message.author
+            })
+            .map(|challenge| challenge.game.id.clone())
+        else {
+            return Err(Error::Generic(String::from(
+                "You have no pending challenge in this channel.",
+            )));
+        };
+
+        let Some(challenge) = challenges.remove(&id) else {
+            return Ok(());
+        };
+        drop(challenges);
+
+        send_reply(
+            client,
+            &message.channel,
+            &message.id,
+            &format!(
+                "Your challenge to <@{}> was cancelled.",
+                challenge.opponent().id
+            ),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Removes challenges nobody answered in time.
+    pub(super) async fn expire_challenges(&self, client: &Client) {
+        let now = Instant::now();
+
+        let expired = {
+            let mut challenges = self.challenges.lock().await;
+            let ids = challenges
+                .values()
+                .filter(|challenge| challenge.expires_at <= now)
+                .map(|challenge| challenge.game.id.clone())
+                .collect::<Vec<_>>();
+
+            ids.iter()
+                .filter_map(|id| challenges.remove(id))
+                .collect::<Vec<_>>()
+        };
+
+        for challenge in expired {
+            send_reply(
+                client,
+                &challenge.game.channel,
+                &challenge.game.id,
+                &format!(
+                    "<@{}>, your challenge to <@{}> expired.",
+                    challenge.challenger,
+                    challenge.opponent().id
+                ),
+            )
+            .await;
+        }
+    }
+
+    /// Removes the challenge addressed to the author, picking the challenger mentioned in the
+    /// message if there are several.
+    async fn take_challenge(&self, client: &Client, message: &Message) -> Result<Challenge, Error> {
+        let challenger = match message
+            .content
+            .as_deref()
+            .and_then(|content| content.split_whitespace().nth(1))
+        {
+            Some(challenger) => match client.resolve_user(challenger).await {
+                Ok(Some(challenger)) => Some(challenger.id),
+                Ok(None) => return Err(Error::Generic(String::from("Failed to find user."))),
+                Err(error) => {
+                    return Err(Error::Fetch {
+                        resource: ResourceType::User,
+                        inner: error,
+                    })
+                }
+            },
+            None => None,
+        };
+
+        let mut challenges = self.challenges.lock().await;
+
+        let Some(id) = challenges
+            .values()
+            .filter(|challenge| {
+                challenge.game.channel == message.channel
+                    && challenge.opponent().id == message.author
+                    && challenger
+                        .as_ref()
+                        .is_none_or(|challenger| *challenger == challenge.challenger)
+            })
+            .max_by_key(|challenge| challenge.expires_at)
+            .map(|challenge| challenge.game.id.clone())
+        else {
+            return Err(Error::Generic(String::from(
+                "You have no pending challenge in this channel.",
+            )));
+        };
+
+        challenges.remove(&id).ok_or_else(|| {
+            Error::Generic(String::from(
+                "You have no pending challenge in this channel.",
+            ))
+        })
+    }
+}
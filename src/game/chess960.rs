@@ -0,0 +1,372 @@
+use chess::{
+    get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks, get_rook_moves, BitBoard,
+    Board, BoardBuilder, ChessMove, Color, File, Piece, Square, EMPTY,
+};
+
+use super::{
+    fen::{self, FenError, Position},
+    rules::{self, IllegalMove},
+    san::{self, ParseError},
+};
+
+/// The number of Chess960 starting positions.
+pub const POSITIONS: u16 = 960;
+
+/// Where the knights go among the five squares left after placing the bishops and queen.
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastleSide {
+    King,
+    Queen,
+}
+
+impl CastleSide {
+    const fn index(self) -> usize {
+        match self {
+            Self::King => 0,
+            Self::Queen => 1,
+        }
+    }
+
+    const fn king_file(self) -> File {
+        match self {
+            Self::King => File::G,
+            Self::Queen => File::C,
+        }
+    }
+
+    const fn rook_file(self) -> File {
+        match self {
+            Self::King => File::F,
+            Self::Queen => File::D,
+        }
+    }
+
+    pub const fn notation(self) -> &'static str {
+        match self {
+            Self::King => "O-O",
+            Self::Queen => "O-O-O",
+        }
+    }
+}
+
+/// The castling rights of a Chess960 game, which the `chess` crate cannot track itself.
+///
+/// Boards of Chess960 games are kept without castling rights, and castling is played as the king
+/// capturing its own rook like UCI engines do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chess960 {
+    /// The files of the rooks each color can still castle with, king side first.
+    rooks: [[Option<File>; 2]; 2],
+}
+
+/// The FEN of a starting position by its Scharnagl number, or `None` if there is no such position.
+pub fn start_fen(index: u16) -> Option<String> {
+    if index >= POSITIONS {
+        return None;
+    }
+
+    let mut index = usize::from(index);
+    let mut rank = [None; 8];
+
+    // The light squared bishop goes on b, d, f or h and the dark squared one on a, c, e or g.
+    rank[index % 4 * 2 + 1] = Some(Piece::Bishop);
+    index /= 4;
+    rank[index % 4 * 2] = Some(Piece::Bishop);
+    index /= 4;
+
+    let mut place = |piece, nth: usize| {
+        if let Some(square) = rank.iter_mut().filter(|square| square.is_none()).nth(nth) {
+            *square = Some(piece);
+        }
+    };
+
+    place(Piece::Queen, index % 6);
+    index /= 6;
+
+    // The second knight is placed first, so the first one's index still counts the same squares.
+    let (first, second) = KNIGHT_PLACEMENTS[index];
+    place(Piece::Knight, second);
+    place(Piece::Knight, first);
+
+    // The king goes between the rooks on the three remaining squares.
+    place(Piece::Rook, 0);
+    place(Piece::King, 0);
+    place(Piece::Rook, 0);
+
+    let pieces = rank
+        .iter()
+        .map(|piece| piece.map_or_else(String::new, |piece| piece.to_string(Color::Black)))
+        .collect::<String>();
+
+    Some(format!(
+        "{pieces}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+        pieces.to_uppercase()
+    ))
+}
+
+impl Chess960 {
+    /// Reads a Chess960 FEN, with castling rights as `KQkq` or the files of the rooks.
+    pub fn from_fen(text: &str) -> Result<(Self, Position), FenError> {
+        let mut fields = text.split_whitespace().collect::<Vec<_>>();
+        let castling = fields.get(2).copied().unwrap_or("-");
+
+        if let Some(field) = fields.get_mut(2) {
+            *field = "-";
+        }
+
+        let position = fen::parse(&fields.join(" "))?;
+        let board = &position.board;
+        let mut this = Self {
+            rooks: [[None; 2]; 2],
+        };
+
+        for right in castling.chars().filter(|right| *right != '-') {
+            let color = if right.is_ascii_uppercase() {
+                Color::White
+            } else {
+                Color::Black
+            };
+            let king = board.king_square(color);
+            let rooks = (*board.pieces(Piece::Rook) & *board.color_combined(color))
+                .filter(|rook| rook.get_rank() == color.to_my_backrank())
+                .map(|rook| rook.get_file())
+                .collect::<Vec<_>>();
+
+            let file = match right.to_ascii_lowercase() {
+                'k' => rooks.iter().copied().max(),
+                'q' => rooks.iter().copied().min(),
+                file @ 'a'..='h' => "abcdefgh".find(file).map(File::from_index),
+                _ => None,
+            };
+
+            let Some(file) = file.filter(|file| rooks.contains(file)) else {
+                return Err(FenError::CastlingMismatch);
+            };
+
+            if king.get_rank() != color.to_my_backrank() || file == king.get_file() {
+                return Err(FenError::CastlingMismatch);
+            }
+
+            let side = if file > king.get_file() {
+                CastleSide::King
+            } else {
+                CastleSide::Queen
+            };
+
+            this.rooks[color.to_index()][side.index()] = Some(file);
+        }
+
+        Ok((this, position))
+    }
+
+    /// The castling move for the side to move, if castling is allowed right now.
+    pub fn castle(&self, board: &Board, side: CastleSide) -> Result<ChessMove, IllegalMove> {
+        let color = board.side_to_move();
+        let rank = color.to_my_backrank();
+        let king = board.king_square(color);
+
+        let Some(rook) =
+            self.rooks[color.to_index()][side.index()].map(|file| Square::make_square(rank, file))
+        else {
+            return Err(IllegalMove::CannotCastle);
+        };
+
+        let king_dest = Square::make_square(rank, side.king_file());
+        let rook_dest = Square::make_square(rank, side.rook_file());
+        let occupied =
+            *board.combined() ^ BitBoard::from_square(king) ^ BitBoard::from_square(rook);
+
+        if king.get_rank() != rank
+            || board.piece_on(rook) != Some(Piece::Rook)
+            || board.color_on(rook) != Some(color)
+            || *board.checkers() != EMPTY
+            || (span(king, king_dest) | span(rook, rook_dest)) & occupied != EMPTY
+        {
+            return Err(IllegalMove::CannotCastle);
+        }
+
+        if span(king, king_dest).any(|square| is_attacked(board, square, !color, occupied)) {
+            return Err(IllegalMove::LeavesKingInCheck);
+        }
+
+        let chess_move = ChessMove::new(king, rook, None);
+
+        if castled(board, chess_move, side).is_none() {
+            return Err(IllegalMove::LeavesKingInCheck);
+        }
+
+        Ok(chess_move)
+    }
+
+    /// Whether any castling move is legal, which the `chess` crate misses when looking for stalemate.
+    pub fn can_castle(&self, board: &Board) -> bool {
+        [CastleSide::King, CastleSide::Queen]
+            .into_iter()
+            .any(|side| self.castle(board, side).is_ok())
+    }
+
+    /// The side the move castles to, if it is a castling move.
+    pub fn castle_side(&self, board: &Board, chess_move: ChessMove) -> Option<CastleSide> {
+        let color = board.side_to_move();
+        let source = chess_move.get_source();
+        let dest = chess_move.get_dest();
+
+        if source != board.king_square(color) || board.color_on(dest) != Some(color) {
+            return None;
+        }
+
+        [CastleSide::King, CastleSide::Queen]
+            .into_iter()
+            .find(|side| {
+                self.rooks[color.to_index()][side.index()] == Some(dest.get_file())
+                    && dest.get_rank() == color.to_my_backrank()
+            })
+    }
+
+    /// Reads castling written as `O-O`, the king taking its rook or the king moving two squares.
+    ///
+    /// Returns `None` for anything else, which is left to the usual move parser.
+    pub fn parse(&self, board: &Board, text: &str) -> Option<Result<ChessMove, ParseError>> {
+        let trimmed = text.trim_end_matches(['+', '#', '!', '?']);
+
+        let side = match trimmed {
+            "O-O" | "0-0" => CastleSide::King,
+            "O-O-O" | "0-0-0" => CastleSide::Queen,
+            _ => {
+                let chess_move = san::parse_uci_syntax(trimmed)?;
+
+                if let Some(side) = self.castle_side(board, chess_move) {
+                    side
+                } else {
+                    let color = board.side_to_move();
+                    let dest = chess_move.get_dest();
+                    let side = [CastleSide::King, CastleSide::Queen]
+                        .into_iter()
+                        .find(|side| dest.get_file() == side.king_file())?;
+
+                    if chess_move.get_source() != board.king_square(color)
+                        || dest.get_rank() != color.to_my_backrank()
+                        || self.rooks[color.to_index()][side.index()].is_none()
+                        || rules::is_legal(board, chess_move)
+                    {
+                        return None;
+                    }
+
+                    side
+                }
+            }
+        };
+
+        Some(self.castle(board, side).map_err(ParseError::Illegal))
+    }
+
+    /// Plays a legal move, returning the new position.
+    pub fn make_move(&mut self, board: &Board, chess_move: ChessMove) -> Board {
+        let after = self
+            .castle_side(board, chess_move)
+            .and_then(|side| castled(board, chess_move, side))
+            .unwrap_or_else(|| board.make_move_new(chess_move));
+
+        self.update(board, chess_move);
+
+        after
+    }
+
+    /// Writes a legal move in standard algebraic notation.
+    pub fn format(&self, board: &Board, chess_move: ChessMove) -> String {
+        match self
+            .castle_side(board, chess_move)
+            .and_then(|side| Some((side, castled(board, chess_move, side)?)))
+        {
+            Some((side, after)) => format!("{}{}", side.notation(), san::check_suffix(&after)),
+            None => san::format(board, chess_move),
+        }
+    }
+
+    /// Drops the castling rights a move loses by moving the king or moving or capturing a rook.
+    pub fn update(&mut self, board: &Board, chess_move: ChessMove) {
+        let mover = board.side_to_move();
+
+        if chess_move.get_source() == board.king_square(mover) {
+            self.rooks[mover.to_index()] = [None; 2];
+        }
+
+        for color in [Color::White, Color::Black] {
+            for rook in &mut self.rooks[color.to_index()] {
+                if rook.is_some_and(|file| {
+                    let square = Square::make_square(color.to_my_backrank(), file);
+
+                    chess_move.get_source() == square || chess_move.get_dest() == square
+                }) {
+                    *rook = None;
+                }
+            }
+        }
+    }
+}
+
+/// The position after castling, or `None` if the king would end up in check.
+pub fn castled(board: &Board, chess_move: ChessMove, side: CastleSide) -> Option<Board> {
+    let color = board.side_to_move();
+    let rank = color.to_my_backrank();
+    let mut builder = BoardBuilder::from(board);
+
+    builder
+        .clear_square(chess_move.get_source())
+        .clear_square(chess_move.get_dest())
+        .piece(
+            Square::make_square(rank, side.king_file()),
+            Piece::King,
+            color,
+        )
+        .piece(
+            Square::make_square(rank, side.rook_file()),
+            Piece::Rook,
+            color,
+        )
+        .side_to_move(!color)
+        .en_passant(None);
+
+    Board::try_from(&builder).ok()
+}
+
+/// The squares on the rank from one square to another, both included.
+fn span(from: Square, to: Square) -> BitBoard {
+    let (low, high) = if from.get_file() < to.get_file() {
+        (from.get_file(), to.get_file())
+    } else {
+        (to.get_file(), from.get_file())
+    };
+
+    (low.to_index()..=high.to_index())
+        .map(|file| {
+            BitBoard::from_square(Square::make_square(from.get_rank(), File::from_index(file)))
+        })
+        .fold(EMPTY, |span, square| span | square)
+}
+
+fn is_attacked(board: &Board, square: Square, by: Color, occupied: BitBoard) -> bool {
+    let attackers = *board.color_combined(by);
+    let queens = *board.pieces(Piece::Queen);
+
+    (get_rook_moves(square, occupied) & (*board.pieces(Piece::Rook) | queens)
+        | get_bishop_moves(square, occupied) & (*board.pieces(Piece::Bishop) | queens)
+        | get_knight_moves(square) & *board.pieces(Piece::Knight)
+        | get_king_moves(square) & *board.pieces(Piece::King)
+        | get_pawn_attacks(square, !by, *board.pieces(Piece::Pawn)))
+        & attackers
+        != EMPTY
+}
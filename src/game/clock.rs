@@ -0,0 +1,202 @@
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use chess::Color;
+use serde::{Deserialize, Serialize};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// The longest base time in minutes and increment in seconds that can be chosen.
+const MAX_BASE_MINUTES: f64 = 180.0;
+const MAX_INCREMENT_SECONDS: u64 = 180;
+
+/// The time added for every move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Bonus {
+    /// Fischer increment, added after every move.
+    Increment(Duration),
+    /// Bronstein delay, giving back the time used up to the delay.
+    Delay(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeControl {
+    Live {
+        base: Duration,
+        bonus: Bonus,
+    },
+    /// A fixed number of days for every move.
+    Correspondence {
+        days: u32,
+    },
+}
+
+impl TimeControl {
+    /// Turns the increment into a Bronstein delay.
+    #[must_use]
+    pub const fn with_delay(self) -> Self {
+        match self {
+            Self::Live {
+                base,
+                bonus: Bonus::Increment(delay) | Bonus::Delay(delay),
+            } => Self::Live {
+                base,
+                bonus: Bonus::Delay(delay),
+            },
+            correspondence @ Self::Correspondence { .. } => correspondence,
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = ();
+
+    /// Parses `<minutes>+<seconds>` or `<days>d`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if let Some(days) = text.strip_suffix('d') {
+            return match days.parse() {
+                Ok(days) if days > 0 => Ok(Self::Correspondence { days }),
+                _ => Err(()),
+            };
+        }
+
+        let (base, increment) = text.split_once('+').ok_or(())?;
+        let base = base.parse::<f64>().map_err(|_| ())?;
+        let increment = increment.parse::<u64>().map_err(|_| ())?;
+
+        if !base.is_finite()
+            || base <= 0.0
+            || base > MAX_BASE_MINUTES
+            || increment > MAX_INCREMENT_SECONDS
+        {
+            return Err(());
+        }
+
+        Ok(Self::Live {
+            base: Duration::try_from_secs_f64(base * 60.0).map_err(|_| ())?,
+            bonus: Bonus::Increment(Duration::from_secs(increment)),
+        })
+    }
+}
+
+impl std::fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Live { base, bonus } => {
+                let minutes = base.as_secs_f64() / 60.0;
+
+                match bonus {
+                    Bonus::Increment(increment) => write!(f, "{minutes}+{}", increment.as_secs()),
+                    Bonus::Delay(delay) => write!(f, "{minutes}+{} (delay)", delay.as_secs()),
+                }
+            }
+            Self::Correspondence { days: 1 } => f.write_str("1 day per move"),
+            Self::Correspondence { days } => write!(f, "{days} days per move"),
+        }
+    }
+}
+
+/// A chess clock for both players.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clock {
+    pub control: TimeControl,
+    /// The time left for white and black at the start of their turn.
+    remaining: [Duration; 2],
+    /// When the current turn started, `None` until the game starts.
+    turn_started: Option<SystemTime>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let time = match control {
+            TimeControl::Live { base, .. } => base,
+            TimeControl::Correspondence { days } => DAY * days,
+        };
+
+        Self {
+            control,
+            remaining: [time, time],
+            turn_started: None,
+        }
+    }
+
+    pub fn start(&mut self, now: SystemTime) {
+        self.turn_started = Some(now);
+    }
+
+    /// The time a player has left, counting down while it is their turn.
+    pub fn remaining(&self, color: Color, side_to_move: Color, now: SystemTime) -> Duration {
+        let remaining = self.remaining[color.to_index()];
+
+        match self.turn_started {
+            Some(started) if color == side_to_move => remaining
+                .saturating_sub(self.charged(now.duration_since(started).unwrap_or_default())),
+            _ => remaining,
+        }
+    }
+
+    pub fn is_flagged(&self, side_to_move: Color, now: SystemTime) -> bool {
+        self.turn_started.is_some() && self.remaining(side_to_move, side_to_move, now).is_zero()
+    }
+
+    /// Stops the mover's clock and starts the opponent's, returning `false` if the mover
+    /// ran out of time.
+    pub fn press(&mut self, mover: Color, now: SystemTime) -> bool {
+        let remaining = self.remaining(mover, mover, now);
+
+        if self.turn_started.is_some() && remaining.is_zero() {
+            return false;
+        }
+
+        self.remaining[mover.to_index()] = match self.control {
+            TimeControl::Live {
+                bonus: Bonus::Increment(increment),
+                ..
+            } => remaining.saturating_add(increment),
+            TimeControl::Live { .. } => remaining,
+            TimeControl::Correspondence { .. } => Self::new(self.control).remaining[0],
+        };
+        self.turn_started = Some(now);
+
+        true
+    }
+
+    /// Shows both players' remaining time.
+    pub fn display(&self, side_to_move: Color, now: SystemTime) -> String {
+        format!(
+            "⏱ White {} · Black {}",
+            format_duration(self.remaining(Color::White, side_to_move, now)),
+            format_duration(self.remaining(Color::Black, side_to_move, now))
+        )
+    }
+
+    /// The part of the elapsed time that is taken off the clock.
+    fn charged(&self, elapsed: Duration) -> Duration {
+        match self.control {
+            TimeControl::Live {
+                bonus: Bonus::Delay(delay),
+                ..
+            } => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match (
+        seconds / 86400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    ) {
+        (0, 0, 0, seconds) if duration < Duration::from_secs(10) => {
+            format!("0:{seconds:02}.{}", duration.subsec_millis() / 100)
+        }
+        (0, 0, minutes, seconds) => format!("{minutes}:{seconds:02}"),
+        (0, hours, minutes, seconds) => format!("{hours}:{minutes:02}:{seconds:02}"),
+        (days, hours, _, _) => format!("{days}d {hours}h"),
+    }
+}
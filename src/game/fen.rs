@@ -0,0 +1,332 @@
+use std::{fmt::Display, str::FromStr};
+
+use chess::{Board, BoardBuilder, CastleRights, Color, Piece, ALL_SQUARES, EMPTY};
+
+/// The standard starting position.
+pub const STANDARD: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// Why a FEN was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    FieldCount(usize),
+    RankCount(usize),
+    RankLength(usize),
+    InvalidPiece(char),
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidCounter(String),
+    KingCount(Color, u32),
+    PawnOnBackRank,
+    OpponentInCheck,
+    EnPassantMismatch,
+    CastlingMismatch,
+    Illegal,
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FieldCount(count) => write!(
+                f,
+                "A FEN has 4 to 6 fields separated by spaces, found {count}."
+            ),
+            Self::RankCount(count) => write!(
+                f,
+                "The board needs 8 ranks separated by `/`, found {count}."
+            ),
+            Self::RankLength(rank) => write!(f, "Rank {rank} does not have exactly 8 squares."),
+            Self::InvalidPiece(piece) => write!(f, "`{piece}` is not a piece."),
+            Self::InvalidSideToMove(side) => {
+                write!(f, "The side to move must be `w` or `b`, not `{side}`.")
+            }
+            Self::InvalidCastling(castling) => write!(
+                f,
+                "`{castling}` is not a valid castling field, use a combination of `KQkq` or `-`."
+            ),
+            Self::InvalidEnPassant(square) => write!(
+                f,
+                "`{square}` is not a valid en passant square, it must be on the 3rd or 6th rank or `-`."
+            ),
+            Self::InvalidCounter(counter) => write!(f, "`{counter}` is not a valid move counter."),
+            Self::KingCount(color, count) => write!(
+                f,
+                "{} has {count} kings, there must be exactly one.",
+                match color {
+                    Color::White => "White",
+                    Color::Black => "Black",
+                }
+            ),
+            Self::PawnOnBackRank => write!(f, "Pawns cannot be on the first or last rank."),
+            Self::OpponentInCheck => {
+                write!(f, "The side that just moved cannot be left in check.")
+            }
+            Self::EnPassantMismatch => write!(
+                f,
+                "The en passant square does not match a pawn that just moved two squares."
+            ),
+            Self::CastlingMismatch => write!(
+                f,
+                "The castling rights do not match where the kings and rooks are."
+            ),
+            Self::Illegal => write!(f, "The position is not legal."),
+        }
+    }
+}
+
+/// A validated starting position.
+pub struct Position {
+    pub board: Board,
+    /// Half-moves since the last capture or pawn move.
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+}
+
+/// Reads a FEN, explaining what is wrong with it if it cannot be played from.
+pub fn parse(fen: &str) -> Result<Position, FenError> {
+    let fields = fen.split_whitespace().collect::<Vec<_>>();
+
+    if !(4..=6).contains(&fields.len()) {
+        return Err(FenError::FieldCount(fields.len()));
+    }
+
+    let ranks = fields[0].split('/').collect::<Vec<_>>();
+
+    if ranks.len() != 8 {
+        return Err(FenError::RankCount(ranks.len()));
+    }
+
+    for (index, rank) in ranks.iter().enumerate() {
+        let mut squares = 0;
+
+        for character in rank.chars() {
+            match character {
+                '1'..='8' => squares += character.to_digit(10).unwrap_or_default(),
+                'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => {
+                    squares += 1;
+                }
+                character => return Err(FenError::InvalidPiece(character)),
+            }
+        }
+
+        if squares != 8 {
+            return Err(FenError::RankLength(8 - index));
+        }
+    }
+
+    if !matches!(fields[1], "w" | "b") {
+        return Err(FenError::InvalidSideToMove(fields[1].to_string()));
+    }
+
+    if fields[2] != "-"
+        && (fields[2].is_empty() || !fields[2].chars().all(|right| "KQkq".contains(right)))
+    {
+        return Err(FenError::InvalidCastling(fields[2].to_string()));
+    }
+
+    if fields[3] != "-"
+        && !(fields[3].len() == 2
+            && matches!(fields[3].as_bytes()[0], b'a'..=b'h')
+            && matches!(fields[3].as_bytes()[1], b'3' | b'6'))
+    {
+        return Err(FenError::InvalidEnPassant(fields[3].to_string()));
+    }
+
+    let counter = |field: Option<&&str>, default| {
+        field.map_or(Ok(default), |field| {
+            field
+                .parse::<u32>()
+                .map_err(|_| FenError::InvalidCounter((*field).to_string()))
+        })
+    };
+    let halfmove_clock = counter(fields.get(4), 0)?;
+    let fullmove_number = counter(fields.get(5), 1)?.max(1);
+
+    // Checked here because the `chess` crate does not reject every board with such pawns.
+    if [ranks[0], ranks[7]]
+        .iter()
+        .any(|rank| rank.contains(['p', 'P']))
+    {
+        return Err(FenError::PawnOnBackRank);
+    }
+
+    if let Ok(board) = Board::from_str(fen) {
+        return Ok(Position {
+            board,
+            halfmove_clock,
+            fullmove_number,
+        });
+    }
+
+    Err(diagnose(fen))
+}
+
+/// Finds out why the `chess` crate rejected a FEN that is well-formed.
+fn diagnose(fen: &str) -> FenError {
+    let Ok(builder) = BoardBuilder::from_str(fen) else {
+        return FenError::Illegal;
+    };
+
+    for color in [Color::White, Color::Black] {
+        let kings = ALL_SQUARES
+            .iter()
+            .filter(|square| builder[**square] == Some((Piece::King, color)))
+            .count();
+
+        if kings != 1 {
+            return FenError::KingCount(color, u32::try_from(kings).unwrap_or(u32::MAX));
+        }
+    }
+
+    let mut flipped = builder;
+    flipped
+        .side_to_move(!builder.get_side_to_move())
+        .en_passant(None);
+
+    if Board::try_from(&flipped).is_ok_and(|board| *board.checkers() != EMPTY) {
+        return FenError::OpponentInCheck;
+    }
+
+    let mut without_en_passant = builder;
+    without_en_passant.en_passant(None);
+
+    if Board::try_from(&without_en_passant).is_ok() {
+        return FenError::EnPassantMismatch;
+    }
+
+    let mut without_castling = without_en_passant;
+    without_castling
+        .castle_rights(Color::White, CastleRights::NoRights)
+        .castle_rights(Color::Black, CastleRights::NoRights);
+
+    if Board::try_from(&without_castling).is_ok() {
+        return FenError::CastlingMismatch;
+    }
+
+    FenError::Illegal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_valid_fens() {
+        let Ok(position) = parse(STANDARD) else {
+            panic!("the standard position was rejected");
+        };
+
+        assert_eq!(position.board, Board::default());
+        assert_eq!(position.halfmove_clock, 0);
+        assert_eq!(position.fullmove_number, 1);
+
+        // The move counters are optional.
+        let Ok(position) = parse("4k3/8/8/8/8/8/8/4K2R w K -") else {
+            panic!("a FEN without move counters was rejected");
+        };
+
+        assert_eq!(position.fullmove_number, 1);
+    }
+
+    #[test]
+    fn explains_malformed_fens() {
+        let cases = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w",
+                FenError::FieldCount(2),
+                "A FEN has 4 to 6 fields separated by spaces, found 2.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+                FenError::RankCount(7),
+                "The board needs 8 ranks separated by `/`, found 7.",
+            ),
+            (
+                "rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                FenError::RankLength(7),
+                "Rank 7 does not have exactly 8 squares.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+                FenError::InvalidPiece('X'),
+                "`X` is not a piece.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+                FenError::InvalidSideToMove(String::from("x")),
+                "The side to move must be `w` or `b`, not `x`.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQxq - 0 1",
+                FenError::InvalidCastling(String::from("KQxq")),
+                "`KQxq` is not a valid castling field, use a combination of `KQkq` or `-`.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e4 0 1",
+                FenError::InvalidEnPassant(String::from("e4")),
+                "`e4` is not a valid en passant square, it must be on the 3rd or 6th rank or `-`.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+                FenError::InvalidCounter(String::from("x")),
+                "`x` is not a valid move counter.",
+            ),
+        ];
+
+        for (fen, error, message) in cases {
+            let result = parse(fen).err();
+
+            assert_eq!(result, Some(error), "{fen}");
+            assert_eq!(
+                result.map(|error| error.to_string()).as_deref(),
+                Some(message)
+            );
+        }
+    }
+
+    #[test]
+    fn explains_illegal_positions() {
+        let cases = [
+            (
+                "rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1",
+                FenError::KingCount(Color::Black, 0),
+                "Black has 0 kings, there must be exactly one.",
+            ),
+            (
+                "4k2P/8/8/8/8/8/8/4K3 w - - 0 1",
+                FenError::PawnOnBackRank,
+                "Pawns cannot be on the first or last rank.",
+            ),
+            (
+                "4k3/8/8/8/8/8/8/p3K3 b - - 0 1",
+                FenError::PawnOnBackRank,
+                "Pawns cannot be on the first or last rank.",
+            ),
+            (
+                "4k3/8/8/8/8/8/4R3/4K3 w - - 0 1",
+                FenError::OpponentInCheck,
+                "The side that just moved cannot be left in check.",
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1",
+                FenError::EnPassantMismatch,
+                "The en passant square does not match a pawn that just moved two squares.",
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+                FenError::CastlingMismatch,
+                "The castling rights do not match where the kings and rooks are.",
+            ),
+        ];
+
+        for (fen, error, message) in cases {
+            let result = parse(fen).err();
+
+            assert_eq!(result, Some(error), "{fen}");
+            assert_eq!(
+                result.map(|error| error.to_string()).as_deref(),
+                Some(message)
+            );
+        }
+    }
+}
@@ -0,0 +1,909 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use chess::{Board, BoardStatus, ChessMove, Color, Piece, EMPTY};
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::{
+        channels::message::{Message, Reply},
+        users::User,
+    },
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    commands::{Error, PREFIX},
+    engine,
+    render::{png, text, BoardView, RenderMode},
+    Client,
+};
+
+pub mod analysis;
+pub mod archive;
+pub mod challenge;
+pub mod chess960;
+pub mod clock;
+pub mod fen;
+pub mod outcome;
+pub mod persistence;
+pub mod pgn;
+pub mod rating;
+pub mod replay;
+pub mod rules;
+pub mod san;
+pub mod stats;
+
+use challenge::Challenge;
+use clock::Clock;
+use outcome::{color_name, Outcome, Termination};
+
+/// A chess game that is being played in a channel.
+pub struct Game {
+    /// The ID of the message that started the game.
+    pub id: String,
+    pub channel: String,
+    /// The server the game is played in, which keeps its own ratings.
+    pub server: String,
+    pub white: User,
+    pub black: User,
+    pub game: chess::Game,
+    pub render_mode: RenderMode,
+    pub moves: Vec<PlayedMove>,
+    pub started_at: SystemTime,
+    /// The FEN the game started from, if not the standard starting position.
+    pub start_fen: Option<String>,
+    /// The castling rights of Chess960 games.
+    pub chess960: Option<chess960::Chess960>,
+    /// Hashes of every position reached, for detecting repetitions.
+    positions: Vec<u64>,
+    /// Half-moves since the last capture or pawn move.
+    halfmove_clock: u32,
+    pub draw_offer: Option<DrawOffer>,
+    pub clock: Option<Clock>,
+    /// How well the bot plays, in games against it.
+    pub difficulty: Option<engine::Difficulty>,
+    /// Whether the result changes the players' ratings.
+    pub rated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayedMove {
+    pub chess_move: ChessMove,
+    pub san: String,
+    /// The mover's time left after the move, in timed games.
+    pub clock: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DrawOffer {
+    #[serde(with = "persistence::ColorDef")]
+    pub by: Color,
+    /// Whether the opponent has moved since the offer, after which the offerer's next move withdraws it.
+    pub opponent_moved: bool,
+}
+
+impl Game {
+    pub fn new(id: String, channel: String, white: User, black: User) -> Self {
+        let game = chess::Game::new();
+        let positions = vec![game.current_position().get_hash()];
+
+        Self {
+            id,
+            channel,
+            server: String::new(),
+            white,
+            black,
+            game,
+            render_mode: RenderMode::default(),
+            moves: Vec::new(),
+            started_at: SystemTime::now(),
+            start_fen: None,
+            chess960: None,
+            positions,
+            halfmove_clock: 0,
+            draw_offer: None,
+            clock: None,
+            difficulty: None,
+            rated: false,
+        }
+    }
+
+    /// Creates a game starting from a custom position.
+    pub fn from_fen(
+        id: String,
+        channel: String,
+        white: User,
+        black: User,
+        fen: &str,
+    ) -> Result<Self, fen::FenError> {
+        let position = fen::parse(fen)?;
+        let game = chess::Game::new_with_board(position.board);
+        let positions = vec![game.current_position().get_hash()];
+
+        Ok(Self {
+            game,
+            start_fen: Some(fen.split_whitespace().collect::<Vec<_>>().join(" ")),
+            positions,
+            halfmove_clock: position.halfmove_clock,
+            ..Self::new(id, channel, white, black)
+        })
+    }
+
+    /// Creates a Chess960 game, see [`chess960::start_fen`] for the starting positions.
+    pub fn chess960(
+        id: String,
+        channel: String,
+        white: User,
+        black: User,
+        fen: &str,
+    ) -> Result<Self, fen::FenError> {
+        let (chess960, position) = chess960::Chess960::from_fen(fen)?;
+        let game = chess::Game::new_with_board(position.board);
+        let positions = vec![game.current_position().get_hash()];
+
+        Ok(Self {
+            game,
+            start_fen: Some(fen.split_whitespace().collect::<Vec<_>>().join(" ")),
+            chess960: Some(chess960),
+            positions,
+            halfmove_clock: position.halfmove_clock,
+            ..Self::new(id, channel, white, black)
+        })
+    }
+
+    pub const fn player(&self, color: Color) -> &User {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+
+    /// The color that made the first move, which is black in some games started from a FEN.
+    pub fn starting_side(&self) -> Color {
+        let side_to_move = self.game.side_to_move();
+
+        if self.moves.len() % 2 == 0 {
+            side_to_move
+        } else {
+            !side_to_move
+        }
+    }
+
+    pub fn is_player(&self, user_id: &str) -> bool {
+        self.white.id == user_id || self.black.id == user_id
+    }
+
+    /// The color the user plays, preferring the side to move when someone plays themselves.
+    pub fn color_of(&self, user_id: &str) -> Option<Color> {
+        let side_to_move = self.game.side_to_move();
+
+        [side_to_move, !side_to_move]
+            .into_iter()
+            .find(|color| self.player(*color).id == user_id)
+    }
+
+    /// Parses a move in algebraic notation or UCI, including Chess960 castling.
+    pub fn parse_move(&self, text: &str) -> Result<ChessMove, san::ParseError> {
+        let board = self.game.current_position();
+
+        self.chess960
+            .and_then(|chess960| chess960.parse(&board, text))
+            .unwrap_or_else(|| san::parse_move(&board, text))
+    }
+
+    /// Parses a move in UCI notation, including Chess960 castling.
+    pub fn parse_uci(&self, text: &str) -> Result<ChessMove, san::ParseError> {
+        let board = self.game.current_position();
+
+        self.chess960
+            .and_then(|chess960| chess960.parse(&board, text))
+            .unwrap_or_else(|| san::parse_uci(&board, text))
+    }
+
+    /// Plays a legal move, returning `false` if the game is already over.
+    pub fn play(&mut self, chess_move: ChessMove) -> bool {
+        let board = self.game.current_position();
+        let mover = board.side_to_move();
+
+        let (san, resets_clock) = match self.chess960.and_then(|chess960| {
+            let side = chess960.castle_side(&board, chess_move)?;
+
+            Some((side, chess960::castled(&board, chess_move, side)?))
+        }) {
+            // The `chess` crate cannot castle in Chess960, so the game continues from the new position.
+            Some((side, castled)) => {
+                if self.game.result().is_some() {
+                    return false;
+                }
+
+                self.game = chess::Game::new_with_board(castled);
+
+                (
+                    format!("{}{}", side.notation(), san::check_suffix(&castled)),
+                    false,
+                )
+            }
+            None => {
+                let resets_clock = board.piece_on(chess_move.get_source()) == Some(Piece::Pawn)
+                    || board.piece_on(chess_move.get_dest()).is_some();
+                let san = san::format(&board, chess_move);
+
+                if !self.game.make_move(chess_move) {
+                    return false;
+                }
+
+                (san, resets_clock)
+            }
+        };
+
+        if let Some(chess960) = &mut self.chess960 {
+            chess960.update(&board, chess_move);
+        }
+
+        self.halfmove_clock = if resets_clock {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        self.moves.push(PlayedMove {
+            chess_move,
+            san,
+            clock: self
+                .clock
+                .as_ref()
+                .map(|clock| clock.remaining(mover, !mover, SystemTime::now())),
+        });
+        self.positions.push(self.game.current_position().get_hash());
+
+        // An offer stands through the opponent's reply and is withdrawn by the offerer's next move.
+        self.draw_offer = match self.draw_offer {
+            Some(offer) if offer.by != mover => Some(DrawOffer {
+                opponent_moved: true,
+                ..offer
+            }),
+            Some(offer) if offer.opponent_moved => None,
+            offer => offer,
+        };
+
+        true
+    }
+
+    /// Checks whether the current position ends the game.
+    pub fn check_outcome(&self) -> Option<Outcome> {
+        let board = self.game.current_position();
+        let current = board.get_hash();
+
+        let termination = match board.status() {
+            BoardStatus::Checkmate => {
+                return Some(Outcome::win(!board.side_to_move(), Termination::Checkmate))
+            }
+            BoardStatus::Stalemate
+                if self
+                    .chess960
+                    .is_some_and(|chess960| chess960.can_castle(&board)) =>
+            {
+                return None
+            }
+            BoardStatus::Stalemate => Termination::Stalemate,
+            BoardStatus::Ongoing if rules::is_insufficient_material(&board) => {
+                Termination::InsufficientMaterial
+            }
+            BoardStatus::Ongoing
+                if self
+                    .positions
+                    .iter()
+                    .filter(|position| **position == current)
+                    .count()
+                    >= 3 =>
+            {
+                Termination::ThreefoldRepetition
+            }
+            BoardStatus::Ongoing if self.halfmove_clock >= 100 => Termination::FiftyMoveRule,
+            BoardStatus::Ongoing => return None,
+        };
+
+        Some(Outcome::draw(termination))
+    }
+
+    /// The outcome when the player to move runs out of time.
+    pub fn timeout_outcome(&self) -> Outcome {
+        let board = self.game.current_position();
+        let winner = !board.side_to_move();
+
+        if rules::has_mating_material(&board, winner) {
+            Outcome::win(winner, Termination::Timeout)
+        } else {
+            Outcome::draw(Termination::TimeoutVsInsufficientMaterial)
+        }
+    }
+
+    /// The clocks of both players, if the game is timed.
+    pub fn clock_display(&self) -> Option<String> {
+        self.clock
+            .as_ref()
+            .map(|clock| clock.display(self.game.side_to_move(), SystemTime::now()))
+    }
+
+    pub fn introduction(&self) -> String {
+        format!(
+            "<@{}> (white) vs <@{}> (black){}{}{}{}. {} to move, use `{PREFIX}move` to play.",
+            self.white.id,
+            self.black.id,
+            if self.chess960.is_some() {
+                " in Chess960"
+            } else {
+                ""
+            },
+            self.difficulty
+                .map_or_else(String::new, |difficulty| format!(" on {difficulty}")),
+            self.clock
+                .as_ref()
+                .map_or_else(String::new, |clock| format!(", {}", clock.control)),
+            if self.rated {
+                format!(", rated {}", rating::Category::of(self.clock.as_ref()))
+            } else {
+                String::new()
+            },
+            match self.game.side_to_move() {
+                Color::White => "White",
+                Color::Black => "Black",
+            }
+        )
+    }
+
+    /// Describes the outcome, mentioning the winner.
+    pub fn announce(&self, outcome: Outcome) -> String {
+        match outcome.winner {
+            Some(color) => format!(
+                "<@{}> wins by {} playing {}! `{}`",
+                self.player(color).id,
+                outcome.termination,
+                color_name(color),
+                outcome.result()
+            ),
+            None => format!(
+                "Draw by {} between <@{}> and <@{}>. `{}`",
+                outcome.termination,
+                self.white.id,
+                self.black.id,
+                outcome.result()
+            ),
+        }
+    }
+}
+
+/// Keeps track of every running game so that the main event loop can route
+/// in-game commands like `!move` to the right game.
+#[derive(Clone, Default)]
+pub struct GameManager {
+    games: Arc<Mutex<HashMap<String, Game>>>,
+    challenges: Arc<Mutex<HashMap<String, Challenge>>>,
+}
+
+impl GameManager {
+    pub async fn start(&self, client: &Client, mut game: Game) -> Result<(), Error> {
+        let mut games = self.games.lock().await;
+
+        if games.values().any(|other| {
+            other.channel == game.channel
+                && (other.is_player(&game.white.id) || other.is_player(&game.black.id))
+        }) {
+            return Err(Error::Generic(String::from(
+                "One of the players is already playing a game in this channel.",
+            )));
+        }
+
+        if let Some(clock) = &mut game.clock {
+            clock.start(SystemTime::now());
+        }
+
+        persistence::save(&client.cache, &game);
+        games.insert(game.id.clone(), game);
+
+        Ok(())
+    }
+
+    /// Starts a game and sends its starting position.
+    pub async fn begin(&self, client: &Client, game: Game, reply_to: &str) -> Result<(), Error> {
+        let content = game.introduction();
+        let board = game.game.current_position();
+        let channel = game.channel.clone();
+        let render_mode = game.render_mode;
+        let id = game.id.clone();
+        let engine_to_move = game.player(board.side_to_move()).id == client.user.id;
+
+        self.start(client, game).await?;
+
+        send_position(
+            client,
+            &channel,
+            Some(reply_to),
+            &content,
+            &board,
+            None,
+            render_mode,
+        )
+        .await;
+
+        if engine_to_move {
+            self.schedule_engine_move(client, id);
+        }
+
+        Ok(())
+    }
+
+    /// Runs everything that depends on time passing, called every second.
+    pub async fn tick(&self, client: &Client) {
+        self.expire_challenges(client).await;
+        self.flag_games(client).await;
+    }
+
+    /// Ends the games where the player to move ran out of time.
+    async fn flag_games(&self, client: &Client) {
+        let now = SystemTime::now();
+
+        let flagged = {
+            let mut games = self.games.lock().await;
+            let ids = games
+                .values()
+                .filter(|game| {
+                    game.clock
+                        .as_ref()
+                        .is_some_and(|clock| clock.is_flagged(game.game.side_to_move(), now))
+                })
+                .map(|game| game.id.clone())
+                .collect::<Vec<_>>();
+
+            ids.iter()
+                .filter_map(|id| games.remove(id))
+                .collect::<Vec<_>>()
+        };
+
+        for game in flagged {
+            let outcome = game.timeout_outcome();
+            let id = game.id.clone();
+
+            finish(client, game, outcome, &id).await;
+        }
+    }
+
+    /// Handles a message if it is an in-game command, returning whether it was handled.
+    pub async fn handle_message(&self, client: &Client, message: &Message) -> Result<bool, Error> {
+        let Some(content) = &message.content else {
+            return Ok(false);
+        };
+
+        let mut args = content.split_whitespace();
+
+        if !args
+            .next()
+            .is_some_and(|command| *command == format!("{PREFIX}move"))
+        {
+            return Ok(false);
+        }
+
+        let mut games = self.games.lock().await;
+        let game = find_game(&mut games, message)?;
+
+        if game.player(game.game.side_to_move()).id != message.author {
+            return Err(Error::Generic(String::from("It is not your turn.")));
+        }
+
+        let Some(text) = args.next() else {
+            return Err(Error::InvalidUsage {
+                message: String::from("Expected a move argument."),
+                usage: format!("{PREFIX}move <move>"),
+            });
+        };
+
+        let chess_move = game
+            .parse_move(text)
+            .map_err(|error| Error::Generic(error.to_string()))?;
+        let id = game.id.clone();
+
+        self.make_move(client, games, &id, chess_move, &message.id)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Plays a move in the game, then announces the new position or the end of the game.
+    async fn make_move(
+        &self,
+        client: &Client,
+        mut games: MutexGuard<'_, HashMap<String, Game>>,
+        id: &str,
+        chess_move: ChessMove,
+        reply_to: &str,
+    ) -> Result<(), Error> {
+        let Some(game) = games.get_mut(id) else {
+            return Ok(());
+        };
+
+        let side_to_move = game.game.side_to_move();
+        if let Some(clock) = &mut game.clock {
+            if !clock.press(side_to_move, SystemTime::now()) {
+                let outcome = game.timeout_outcome();
+                let Some(game) = games.remove(id) else {
+                    return Ok(());
+                };
+                drop(games);
+
+                finish(client, game, outcome, reply_to).await;
+
+                return Ok(());
+            }
+        }
+
+        if !game.play(chess_move) {
+            return Err(Error::Generic(String::from("This game is already over.")));
+        }
+
+        let board = game.game.current_position();
+
+        if let Some(outcome) = game.check_outcome() {
+            let Some(game) = games.remove(id) else {
+                return Ok(());
+            };
+            drop(games);
+
+            finish(client, game, outcome, reply_to).await;
+
+            return Ok(());
+        }
+
+        let content = format!(
+            "<@{}> to move.{}{}",
+            game.player(board.side_to_move()).id,
+            if *board.checkers() == EMPTY {
+                ""
+            } else {
+                " Check!"
+            },
+            game.clock_display()
+                .map_or_else(String::new, |clock| format!("\n{clock}"))
+        );
+        let channel = game.channel.clone();
+        let render_mode = game.render_mode;
+        let engine_to_move = game.player(board.side_to_move()).id == client.user.id;
+        persistence::save(&client.cache, game);
+        drop(games);
+
+        send_position(
+            client,
+            &channel,
+            Some(reply_to),
+            &content,
+            &board,
+            Some(chess_move),
+            render_mode,
+        )
+        .await;
+
+        if engine_to_move {
+            self.schedule_engine_move(client, id.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Lets the engine reply in the background if it is the bot's turn in the game.
+    pub fn schedule_engine_move(&self, client: &Client, id: String) {
+        let this = self.clone();
+        let client = client.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = this.play_engine_move(&client, &id).await {
+                dbg!(&format!("Engine failed to move in game {id}: {error}"));
+            }
+        });
+    }
+
+    async fn play_engine_move(&self, client: &Client, id: &str) -> Result<(), Error> {
+        let (position, difficulty, ply, channel) = {
+            let games = self.games.lock().await;
+            let Some(game) = games.get(id) else {
+                return Ok(());
+            };
+
+            if game.player(game.game.side_to_move()).id != client.user.id {
+                return Ok(());
+            }
+
+            (
+                engine::Position::from(game),
+                game.difficulty,
+                game.moves.len(),
+                game.channel.clone(),
+            )
+        };
+
+        let text = match client.engine.best_move(&position, difficulty).await {
+            Ok(text) => text,
+            Err(error) => {
+                send_reply(
+                    client,
+                    &channel,
+                    id,
+                    &format!("{error} Use `{PREFIX}resign` to end the game."),
+                )
+                .await;
+
+                return Err(Error::Generic(error.to_string()));
+            }
+        };
+
+        let games = self.games.lock().await;
+        let Some(game) = games.get(id) else {
+            return Ok(());
+        };
+
+        // The game went on without the engine while it was thinking, e.g. after a resignation.
+        if game.moves.len() != ply {
+            return Ok(());
+        }
+
+        let chess_move = game.parse_uci(&text).map_err(|error| {
+            Error::Generic(format!("The engine played an illegal move {text}: {error}"))
+        })?;
+
+        self.make_move(client, games, id, chess_move, id).await
+    }
+
+    /// Whether the user is playing a game in the channel.
+    pub async fn is_playing(&self, channel: &str, user_id: &str) -> bool {
+        self.games
+            .lock()
+            .await
+            .values()
+            .any(|game| game.channel == channel && game.is_player(user_id))
+    }
+
+    /// Whether a game being played is at this position.
+    pub async fn is_live_position(&self, board: &Board) -> bool {
+        self.games
+            .lock()
+            .await
+            .values()
+            .any(|game| game.game.current_position() == *board)
+    }
+
+    /// Exports the game with the given ID, or the one the author is playing in the channel.
+    pub async fn ongoing_pgn(&self, id: Option<&str>, message: &Message) -> Option<String> {
+        let mut games = self.games.lock().await;
+
+        let game = match id {
+            Some(id) => games.get(id),
+            None => find_game(&mut games, message).ok().map(|game| &*game),
+        };
+
+        game.map(|game| pgn::export(game, None))
+    }
+
+    pub async fn resign(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let mut games = self.games.lock().await;
+        let game = find_game(&mut games, message)?;
+        let Some(color) = game.color_of(&message.author) else {
+            return Ok(());
+        };
+
+        game.game.resign(color);
+        let id = game.id.clone();
+
+        let Some(game) = games.remove(&id) else {
+            return Ok(());
+        };
+        drop(games);
+
+        finish(
+            client,
+            game,
+            Outcome::win(!color, Termination::Resignation),
+            &message.id,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    pub async fn offer_draw(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let mut games = self.games.lock().await;
+        let game = find_game(&mut games, message)?;
+        let Some(color) = game.color_of(&message.author) else {
+            return Ok(());
+        };
+
+        match game.draw_offer {
+            Some(offer) if offer.by == color => {
+                return Err(Error::Generic(String::from(
+                    "You have already offered a draw.",
+                )))
+            }
+            Some(_) => {
+                return Err(Error::Generic(format!(
+                    "Your opponent has already offered a draw, use `{PREFIX}accept-draw` or `{PREFIX}decline-draw` to answer it."
+                )))
+            }
+            None => {}
+        }
+
+        game.game.offer_draw(color);
+        game.draw_offer = Some(DrawOffer {
+            by: color,
+            opponent_moved: false,
+        });
+        persistence::save(&client.cache, game);
+
+        let content = format!(
+            "<@{}>, <@{}> offers a draw. Answer with `{PREFIX}accept-draw` or `{PREFIX}decline-draw`, the offer stands until their next move.",
+            game.player(!color).id,
+            message.author
+        );
+        let channel = game.channel.clone();
+        drop(games);
+
+        send_reply(client, &channel, &message.id, &content).await;
+
+        Ok(())
+    }
+
+    pub async fn accept_draw(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let mut games = self.games.lock().await;
+        let game = find_game(&mut games, message)?;
+        let offer = take_opponent_offer(game, &message.author)?;
+
+        // `chess::Game` only honours offers made in the last two actions, so renew it first.
+        game.game.offer_draw(offer.by);
+        game.game.accept_draw();
+        let id = game.id.clone();
+
+        let Some(game) = games.remove(&id) else {
+            return Ok(());
+        };
+        drop(games);
+
+        finish(
+            client,
+            game,
+            Outcome::draw(Termination::DrawAgreement),
+            &message.id,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    pub async fn decline_draw(&self, client: &Client, message: &Message) -> Result<(), Error> {
+        let mut games = self.games.lock().await;
+        let game = find_game(&mut games, message)?;
+        let offer = take_opponent_offer(game, &message.author)?;
+        persistence::save(&client.cache, game);
+
+        let content = format!(
+            "<@{}>, your draw offer was declined.",
+            game.player(offer.by).id
+        );
+        let channel = game.channel.clone();
+        drop(games);
+
+        send_reply(client, &channel, &message.id, &content).await;
+
+        Ok(())
+    }
+}
+
+/// Finds the game the author of the message is playing in its channel.
+fn find_game<'a>(
+    games: &'a mut HashMap<String, Game>,
+    message: &Message,
+) -> Result<&'a mut Game, Error> {
+    games
+        .values_mut()
+        .find(|game| game.channel == message.channel && game.is_player(&message.author))
+        .ok_or_else(|| Error::Generic(String::from("You are not playing a game in this channel.")))
+}
+
+/// Removes the draw offer made by the user's opponent.
+fn take_opponent_offer(game: &mut Game, user_id: &str) -> Result<DrawOffer, Error> {
+    match game.draw_offer {
+        Some(offer) if game.player(!offer.by).id == user_id => {
+            game.draw_offer = None;
+
+            Ok(offer)
+        }
+        _ => Err(Error::Generic(String::from(
+            "Your opponent has not offered a draw.",
+        ))),
+    }
+}
+
+async fn send_reply(client: &Client, channel: &str, reply_to: &str, content: &str) {
+    let _ = client
+        .driver
+        .message_send(
+            channel,
+            &DataMessageSend::new()
+                .set_content(content)
+                .set_replies(vec![Reply {
+                    id: reply_to.to_string(),
+                    mention: false,
+                }]),
+        )
+        .await;
+}
+
+/// Announces the end of a game that has already been removed from the manager.
+pub async fn finish(client: &Client, game: Game, outcome: Outcome, reply_to: &str) {
+    persistence::delete(&client.cache, &game.id);
+    pgn::store(&client.cache, &game, outcome);
+    archive::store(&client.cache, &game, outcome);
+    stats::record(&client.cache, &game, outcome, &client.user.id);
+
+    let announcement = match rating::record(&client.cache, &game, outcome) {
+        Some(changes) => format!("{}\n{changes}", game.announce(outcome)),
+        None => game.announce(outcome),
+    };
+
+    send_position(
+        client,
+        &game.channel,
+        Some(reply_to),
+        &announcement,
+        &game.game.current_position(),
+        game.moves.last().map(|played| played.chess_move),
+        game.render_mode,
+    )
+    .await;
+}
+
+/// Sends a position along with the board, replying to the given message if there is one.
+pub async fn send_position(
+    client: &Client,
+    channel: &str,
+    reply_to: Option<&str>,
+    content: &str,
+    board: &Board,
+    last_move: Option<ChessMove>,
+    render_mode: RenderMode,
+) {
+    let view = BoardView::new(board, last_move);
+
+    let (content, attachments) = match render_mode {
+        RenderMode::Text => (format!("{content}\n{}", text::render(&view)), Vec::new()),
+        RenderMode::Image => {
+            let uploaded = match png::render(&view) {
+                Some(png) => client
+                    .upload_attachment("board.png", png)
+                    .await
+                    .map_err(|error| format!("Failed to upload board image: {error:?}")),
+                None => Err(String::from("Failed to encode board image")),
+            };
+
+            match uploaded {
+                Ok(id) => (content.to_string(), vec![id]),
+                Err(error) => {
+                    dbg!(&error);
+
+                    (format!("{content}\n{}", text::render(&view)), Vec::new())
+                }
+            }
+        }
+    };
+
+    let mut data = DataMessageSend::new()
+        .set_content(&content)
+        .set_attachments(attachments);
+
+    if let Some(reply_to) = reply_to {
+        data = data.set_replies(vec![Reply {
+            id: reply_to.to_string(),
+            mention: false,
+        }]);
+    }
+
+    let _ = client.driver.message_send(channel, &data).await;
+}
@@ -0,0 +1,71 @@
+use chess::Color;
+use serde::{Deserialize, Serialize};
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    Resignation,
+    DrawAgreement,
+    Timeout,
+    TimeoutVsInsufficientMaterial,
+}
+
+impl std::fmt::Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Checkmate => "checkmate",
+            Self::Stalemate => "stalemate",
+            Self::InsufficientMaterial => "insufficient material",
+            Self::ThreefoldRepetition => "threefold repetition",
+            Self::FiftyMoveRule => "the fifty-move rule",
+            Self::Resignation => "resignation",
+            Self::DrawAgreement => "agreement",
+            Self::Timeout => "timeout",
+            Self::TimeoutVsInsufficientMaterial => "timeout vs insufficient material",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The winning color, or `None` for a draw.
+    pub winner: Option<Color>,
+    pub termination: Termination,
+}
+
+impl Outcome {
+    pub const fn win(winner: Color, termination: Termination) -> Self {
+        Self {
+            winner: Some(winner),
+            termination,
+        }
+    }
+
+    pub const fn draw(termination: Termination) -> Self {
+        Self {
+            winner: None,
+            termination,
+        }
+    }
+
+    /// The result as written in PGN.
+    pub const fn result(&self) -> &'static str {
+        match self.winner {
+            Some(Color::White) => "1-0",
+            Some(Color::Black) => "0-1",
+            None => "1/2-1/2",
+        }
+    }
+}
+
+pub const fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use chess::Color;
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::Error, engine::Difficulty, render::RenderMode, Client, RedisSavedGame, ResourceType,
+};
+
+use super::{clock::Clock, DrawOffer, Game, GameManager};
+
+const ACTIVE_GAMES_KEY: &str = "active-games";
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Color")]
+pub enum ColorDef {
+    White,
+    Black,
+}
+
+/// Everything needed to bring a game back after a restart.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub id: String,
+    pub channel: String,
+    #[serde(default)]
+    pub server: String,
+    pub white: String,
+    pub black: String,
+    /// The moves played so far in UCI notation.
+    pub moves: Vec<String>,
+    #[serde(default)]
+    pub clock_times: Vec<Option<Duration>>,
+    #[serde(default = "SystemTime::now")]
+    pub started_at: SystemTime,
+    #[serde(default)]
+    pub start_fen: Option<String>,
+    #[serde(default)]
+    pub chess960: bool,
+    pub render_mode: RenderMode,
+    pub clock: Option<Clock>,
+    pub draw_offer: Option<DrawOffer>,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    #[serde(default)]
+    pub rated: bool,
+}
+
+impl From<&Game> for SavedGame {
+    fn from(game: &Game) -> Self {
+        Self {
+            id: game.id.clone(),
+            channel: game.channel.clone(),
+            server: game.server.clone(),
+            white: game.white.id.clone(),
+            black: game.black.id.clone(),
+            moves: game
+                .moves
+                .iter()
+                .map(|played| played.chess_move.to_string())
+                .collect(),
+            clock_times: game.moves.iter().map(|played| played.clock).collect(),
+            started_at: game.started_at,
+            start_fen: game.start_fen.clone(),
+            chess960: game.chess960.is_some(),
+            render_mode: game.render_mode,
+            clock: game.clock.clone(),
+            draw_offer: game.draw_offer,
+            difficulty: game.difficulty,
+            rated: game.rated,
+        }
+    }
+}
+
+impl SavedGame {
+    async fn into_game(self, client: &Client) -> Result<Game, Error> {
+        let (white, black) = match (
+            client.fetch_user(&self.white).await,
+            client.fetch_user(&self.black).await,
+        ) {
+            (Ok(white), Ok(black)) => (white, black),
+            (Err(error), _) | (_, Err(error)) => {
+                return Err(Error::Fetch {
+                    resource: ResourceType::User,
+                    inner: error,
+                })
+            }
+        };
+
+        let mut game = match &self.start_fen {
+            Some(fen) if self.chess960 => Game::chess960(self.id, self.channel, white, black, fen)
+                .map_err(|error| Error::Generic(format!("Invalid saved FEN {fen}: {error}")))?,
+            Some(fen) => Game::from_fen(self.id, self.channel, white, black, fen)
+                .map_err(|error| Error::Generic(format!("Invalid saved FEN {fen}: {error}")))?,
+            None => Game::new(self.id, self.channel, white, black),
+        };
+
+        for text in &self.moves {
+            let chess_move = game
+                .parse_uci(text)
+                .map_err(|error| Error::Generic(format!("Invalid saved move {text}: {error}")))?;
+
+            game.play(chess_move);
+        }
+
+        for (played, clock) in game.moves.iter_mut().zip(self.clock_times) {
+            played.clock = clock;
+        }
+
+        game.started_at = self.started_at;
+        game.render_mode = self.render_mode;
+        game.draw_offer = self.draw_offer;
+        game.server = self.server;
+        game.difficulty = self.difficulty;
+        game.rated = self.rated;
+        // The time that passed while the bot was down is not charged to anyone.
+        game.clock = self.clock.map(|mut clock| {
+            clock.start(SystemTime::now());
+            clock
+        });
+
+        Ok(game)
+    }
+}
+
+pub fn save(cache: &redis::Client, game: &Game) {
+    let result = cache.get_connection().and_then(|mut conn| {
+        conn.hset::<_, _, _, ()>(
+            ACTIVE_GAMES_KEY,
+            &game.id,
+            RedisSavedGame(SavedGame::from(game)),
+        )
+    });
+
+    if let Err(error) = result {
+        dbg!(&format!("Failed to save game {}: {error}", game.id));
+    }
+}
+
+pub fn delete(cache: &redis::Client, id: &str) {
+    let result = cache
+        .get_connection()
+        .and_then(|mut conn| conn.hdel::<_, _, ()>(ACTIVE_GAMES_KEY, id));
+
+    if let Err(error) = result {
+        dbg!(&format!("Failed to delete game {id}: {error}"));
+    }
+}
+
+impl GameManager {
+    /// Loads the games that were running before the bot restarted.
+    pub async fn restore(&self, client: &Client) {
+        let saved = match client
+            .cache
+            .get_connection()
+            .and_then(|mut conn| conn.hgetall::<_, HashMap<String, String>>(ACTIVE_GAMES_KEY))
+        {
+            Ok(saved) => saved,
+            Err(error) => {
+                dbg!(&format!("Failed to load saved games: {error}"));
+
+                return;
+            }
+        };
+
+        let mut games = self.games.lock().await;
+        let mut engine_games = Vec::new();
+
+        for (id, json) in saved {
+            // Games saved by an older version or corrupted in Redis are dropped instead of
+            // stopping the bot from starting.
+            let saved = match serde_json::from_str::<SavedGame>(&json) {
+                Ok(saved) => saved,
+                Err(error) => {
+                    dbg!(&format!("Dropping unreadable saved game {id}: {error}"));
+                    delete(&client.cache, &id);
+
+                    continue;
+                }
+            };
+
+            match saved.into_game(client).await {
+                Ok(game) => {
+                    if game.player(game.game.side_to_move()).id == client.user.id {
+                        engine_games.push(game.id.clone());
+                    }
+
+                    games.insert(game.id.clone(), game);
+                }
+                Err(error) => {
+                    dbg!(&format!("Failed to restore game {id}: {error}"));
+                }
+            }
+        }
+
+        drop(games);
+
+        for id in engine_games {
+            self.schedule_engine_move(client, id);
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chess::{Board, ChessMove, Color};
+use once_cell::sync::Lazy;
+use redis::Commands;
+use regex::Regex;
+
+use super::{
+    chess960::Chess960,
+    clock::{Bonus, TimeControl},
+    fen,
+    outcome::Outcome,
+    san, Game,
+};
+
+const FINISHED_PGNS_KEY: &str = "finished-pgns";
+const LAST_GAMES_KEY: &str = "last-games";
+const LINE_WIDTH: usize = 80;
+
+static TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\[(\w+)\s+"((?:[^"\\]|\\.)*)"\s*\]"#).unwrap());
+
+static MOVE_NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+\.+").unwrap());
+
+/// The opening of a code block with its language, like ```` ```pgn ````.
+static CODE_FENCE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"```[\w-]*").unwrap());
+
+/// Writes the game as PGN, with `None` as the outcome for games still being played.
+pub fn export(game: &Game, outcome: Option<Outcome>) -> String {
+    let event = if game.rated {
+        "Rated game"
+    } else {
+        "Casual game"
+    };
+    let mut tags = vec![
+        ("Event", String::from(event)),
+        ("Site", String::from("Revolt")),
+        ("Date", format_date(game.started_at)),
+        ("Round", String::from("-")),
+        ("White", game.white.username.clone()),
+        ("Black", game.black.username.clone()),
+        (
+            "Result",
+            outcome.map_or_else(|| String::from("*"), |outcome| outcome.result().to_string()),
+        ),
+        (
+            "TimeControl",
+            game.clock.as_ref().map_or_else(
+                || String::from("-"),
+                |clock| time_control_tag(clock.control),
+            ),
+        ),
+    ];
+
+    if let Some(outcome) = outcome {
+        tags.push(("Termination", outcome.termination.to_string()));
+    }
+
+    if game.chess960.is_some() {
+        tags.push(("Variant", String::from("Chess960")));
+    }
+
+    if let Some(fen) = &game.start_fen {
+        tags.push(("SetUp", String::from("1")));
+        tags.push(("FEN", fen.clone()));
+    }
+
+    let (mut color, mut number) = first_move(game.start_fen.as_deref(), game.chess960.is_some());
+
+    let mut pgn = tags
+        .into_iter()
+        .map(|(name, value)| format!("[{name} \"{}\"]\n", escape_tag(&value)))
+        .collect::<String>();
+
+    pgn.push('\n');
+
+    let mut tokens = Vec::new();
+
+    for (ply, played) in game.moves.iter().enumerate() {
+        if color == Color::White {
+            tokens.push(format!("{number}."));
+        } else if ply == 0 {
+            tokens.push(format!("{number}..."));
+        }
+
+        tokens.push(played.san.clone());
+
+        if let Some(clock) = played.clock {
+            tokens.push(format!("{{[%clk {}]}}", format_clock(clock)));
+        }
+
+        if color == Color::Black {
+            number += 1;
+        }
+
+        color = !color;
+    }
+
+    tokens.push(outcome.map_or("*", |outcome| outcome.result()).to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    pgn
+}
+
+/// Keeps the PGN of a finished game so it can still be exported.
+pub fn store(cache: &redis::Client, game: &Game, outcome: Outcome) {
+    let pgn = export(game, Some(outcome));
+
+    let result = cache.get_connection().and_then(|mut conn| {
+        conn.hset::<_, _, _, ()>(FINISHED_PGNS_KEY, &game.id, pgn)?;
+        conn.hset_multiple::<_, _, _, ()>(
+            LAST_GAMES_KEY,
+            &[(&game.white.id, &game.id), (&game.black.id, &game.id)],
+        )
+    });
+
+    if let Err(error) = result {
+        dbg!(&format!("Failed to store PGN of game {}: {error}", game.id));
+    }
+}
+
+/// Loads the PGN of a finished game, or of the user's last game if no ID is given.
+pub fn load(
+    cache: &redis::Client,
+    id: Option<&str>,
+    user_id: &str,
+) -> crate::Result<Option<String>> {
+    let mut conn = cache.get_connection()?;
+
+    let id = match id {
+        Some(id) => id.to_string(),
+        None => match conn.hget::<_, _, Option<String>>(LAST_GAMES_KEY, user_id)? {
+            Some(id) => id,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(conn.hget(FINISHED_PGNS_KEY, id)?)
+}
+
+/// The color making the first move and its move number, taken from the starting FEN.
+pub fn first_move(start_fen: Option<&str>, chess960: bool) -> (Color, u32) {
+    start_fen
+        .and_then(|fen| {
+            if chess960 {
+                Chess960::from_fen(fen).ok().map(|(_, position)| position)
+            } else {
+                fen::parse(fen).ok()
+            }
+        })
+        .map_or((Color::White, 1), |position| {
+            (position.board.side_to_move(), position.fullmove_number)
+        })
+}
+
+/// Formats a date as `YYYY.MM.DD` in UTC.
+pub fn format_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() / 86400);
+    let (year, month, day) = civil_from_days(i64::try_from(days).unwrap_or_default());
+
+    format!("{year:04}.{month:02}.{day:02}")
+}
+
+/// Converts days since 1970-01-01 to a year, month and day.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn time_control_tag(control: TimeControl) -> String {
+    match control {
+        TimeControl::Live { base, bonus } => {
+            let (Bonus::Increment(bonus) | Bonus::Delay(bonus)) = bonus;
+
+            format!("{}+{}", base.as_secs(), bonus.as_secs())
+        }
+        TimeControl::Correspondence { days } => format!("1/{}", u64::from(days) * 86400),
+    }
+}
+
+/// Escapes quotes and backslashes in a tag value as the PGN standard asks.
+fn escape_tag(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Undoes the escaping of quotes and backslashes in a tag value.
+fn unescape_tag(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            unescaped.extend(characters.next());
+        } else {
+            unescaped.push(character);
+        }
+    }
+
+    unescaped
+}
+
+/// A game read from PGN.
+pub struct ParsedPgn {
+    pub tags: Vec<(String, String)>,
+    pub start: Board,
+    /// The castling rights at the start of Chess960 games.
+    pub chess960: Option<Chess960>,
+    pub moves: Vec<ChessMove>,
+}
+
+impl ParsedPgn {
+    /// The position after the last move.
+    pub fn final_position(&self) -> Board {
+        let mut board = self.start;
+        let mut castling = self.chess960;
+
+        for chess_move in &self.moves {
+            board = match &mut castling {
+                Some(castling) => castling.make_move(&board, *chess_move),
+                None => board.make_move_new(*chess_move),
+            };
+        }
+
+        board
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads the main line of a PGN, ignoring comments, variations and annotations.
+pub fn parse(text: &str) -> Result<ParsedPgn, String> {
+    let text = CODE_FENCE_REGEX.replace_all(text, " ");
+    let tags = TAG_REGEX
+        .captures_iter(&text)
+        .map(|captures| (captures[1].to_string(), unescape_tag(&captures[2])))
+        .collect::<Vec<_>>();
+
+    let movetext = TAG_REGEX.replace_all(&text, " ");
+    let mut cleaned = String::new();
+    let mut variation_depth = 0usize;
+    let mut in_comment = false;
+    let mut in_line_comment = false;
+
+    for character in movetext.chars() {
+        if in_comment {
+            in_comment = character != '}';
+        } else if in_line_comment {
+            in_line_comment = character != '\n';
+        } else {
+            match character {
+                '{' => in_comment = true,
+                ';' => in_line_comment = true,
+                '(' => variation_depth += 1,
+                ')' => variation_depth = variation_depth.saturating_sub(1),
+                '`' => cleaned.push(' '),
+                character if variation_depth == 0 => cleaned.push(character),
+                _ => {}
+            }
+        }
+
+        if !in_comment && !in_line_comment && matches!(character, '}' | '\n') {
+            cleaned.push(' ');
+        }
+    }
+
+    let is_chess960 = tags.iter().any(|(name, value)| {
+        name == "Variant"
+            && matches!(
+                value.to_lowercase().as_str(),
+                "chess960" | "chess 960" | "fischerandom"
+            )
+    });
+    let fen = tags
+        .iter()
+        .find(|(name, _)| name == "FEN")
+        .map_or(fen::STANDARD, |(_, fen)| fen.as_str());
+
+    let (start, chess960) = if is_chess960 {
+        let (chess960, position) =
+            Chess960::from_fen(fen).map_err(|error| format!("Invalid FEN `{fen}`: {error}"))?;
+
+        (position.board, Some(chess960))
+    } else {
+        let position = fen::parse(fen).map_err(|error| format!("Invalid FEN `{fen}`: {error}"))?;
+
+        (position.board, None)
+    };
+
+    let mut board = start;
+    let mut castling = chess960;
+    let mut moves = Vec::new();
+
+    for token in cleaned.split_whitespace() {
+        if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+            continue;
+        }
+
+        let token = MOVE_NUMBER_REGEX.replace(token, "");
+
+        if token.is_empty() || token.starts_with('$') {
+            continue;
+        }
+
+        let chess_move = castling
+            .and_then(|castling| castling.parse(&board, &token))
+            .unwrap_or_else(|| san::parse_san(&board, &token))
+            .map_err(|error| {
+                format!(
+                    "Could not read move {} `{token}`: {error}",
+                    moves.len() / 2 + 1
+                )
+            })?;
+
+        board = match &mut castling {
+            Some(castling) => castling.make_move(&board, chess_move),
+            None => board.make_move_new(chess_move),
+        };
+        moves.push(chess_move);
+    }
+
+    Ok(ParsedPgn {
+        tags,
+        start,
+        chess960,
+        moves,
+    })
+}
@@ -0,0 +1,322 @@
+use std::{f64::consts::PI, str::FromStr};
+
+use chess::Color;
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+use crate::RedisRating;
+
+use super::{
+    clock::{Bonus, Clock, TimeControl},
+    outcome::Outcome,
+    Game,
+};
+
+/// Converts between the Glicko and Glicko-2 scales.
+const SCALE: f64 = 173.7178;
+/// How much the volatility can change, between 0.3 and 1.2 in the Glicko-2 paper.
+const TAU: f64 = 0.5;
+const CONVERGENCE: f64 = 0.000_001;
+const MIN_DEVIATION: f64 = 45.0;
+const MAX_DEVIATION: f64 = 350.0;
+/// Ratings less certain than this are shown as provisional.
+const PROVISIONAL_DEVIATION: f64 = 110.0;
+
+/// The rating pools, by how long a game is expected to last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Bullet,
+    Blitz,
+    Rapid,
+    Classical,
+    Correspondence,
+}
+
+impl Category {
+    pub const ALL: [Self; 5] = [
+        Self::Bullet,
+        Self::Blitz,
+        Self::Rapid,
+        Self::Classical,
+        Self::Correspondence,
+    ];
+
+    /// Picks the pool from the base time plus 40 moves' worth of bonus time.
+    pub fn of(clock: Option<&Clock>) -> Self {
+        match clock.map(|clock| clock.control) {
+            Some(TimeControl::Live { base, bonus }) => {
+                let (Bonus::Increment(bonus) | Bonus::Delay(bonus)) = bonus;
+
+                match base.saturating_add(bonus.saturating_mul(40)).as_secs() {
+                    0..=179 => Self::Bullet,
+                    180..=479 => Self::Blitz,
+                    480..=1499 => Self::Rapid,
+                    _ => Self::Classical,
+                }
+            }
+            // Games without a clock can last as long as correspondence games.
+            Some(TimeControl::Correspondence { .. }) | None => Self::Correspondence,
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.to_string() == s.to_lowercase())
+            .ok_or(())
+    }
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Bullet => "bullet",
+            Self::Blitz => "blitz",
+            Self::Rapid => "rapid",
+            Self::Classical => "classical",
+            Self::Correspondence => "correspondence",
+        })
+    }
+}
+
+/// A Glicko-2 rating on the Glicko scale.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    pub games: u32,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: 1500.0,
+            deviation: MAX_DEVIATION,
+            volatility: 0.06,
+            games: 0,
+        }
+    }
+}
+
+impl Rating {
+    pub fn is_provisional(&self) -> bool {
+        self.deviation > PROVISIONAL_DEVIATION
+    }
+
+    /// The rating after a game scoring 1 for a win, ½ for a draw and 0 for a loss.
+    #[must_use]
+    pub fn update(self, opponent: Self, score: f64) -> Self {
+        self.update_period(&[(opponent, score)])
+    }
+
+    /// The rating after a rating period of games against the given opponents with their scores.
+    #[must_use]
+    pub fn update_period(self, results: &[(Self, f64)]) -> Self {
+        let mu = (self.rating - 1500.0) / SCALE;
+        let phi = self.deviation / SCALE;
+
+        let (information, improvement) = results.iter().fold(
+            (0.0, 0.0),
+            |(information, improvement): (f64, f64), (opponent, score)| {
+                let opponent_mu = (opponent.rating - 1500.0) / SCALE;
+                let opponent_phi = opponent.deviation / SCALE;
+
+                let g = 1.0 / (1.0 + 3.0 * opponent_phi.powi(2) / PI.powi(2)).sqrt();
+                let expected = 1.0 / (1.0 + (-g * (mu - opponent_mu)).exp());
+
+                (
+                    (g.powi(2) * expected).mul_add(1.0 - expected, information),
+                    g.mul_add(score - expected, improvement),
+                )
+            },
+        );
+        let variance = 1.0 / information;
+        let delta = variance * improvement;
+
+        let volatility = self.new_volatility(phi, variance, delta);
+        let pre_phi = phi.hypot(volatility);
+        let new_phi = 1.0 / (1.0 / pre_phi.powi(2) + 1.0 / variance).sqrt();
+        let new_mu = new_phi.powi(2).mul_add(improvement, mu);
+
+        Self {
+            rating: SCALE.mul_add(new_mu, 1500.0),
+            deviation: (SCALE * new_phi).clamp(MIN_DEVIATION, MAX_DEVIATION),
+            volatility,
+            games: self
+                .games
+                .saturating_add(u32::try_from(results.len()).unwrap_or(u32::MAX)),
+        }
+    }
+
+    /// Finds the new volatility with the Illinois algorithm from the Glicko-2 paper.
+    fn new_volatility(&self, phi: f64, variance: f64, delta: f64) -> f64 {
+        let a = self.volatility.powi(2).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+
+            ex * (delta.powi(2) - phi.powi(2) - variance - ex)
+                / (2.0 * (phi.powi(2) + variance + ex).powi(2))
+                - (x - a) / TAU.powi(2)
+        };
+
+        let mut low = a;
+        let mut high = if delta.powi(2) > phi.powi(2) + variance {
+            (delta.powi(2) - phi.powi(2) - variance).ln()
+        } else {
+            let mut k = 1.0;
+
+            while f(a - k * TAU) < 0.0 {
+                k += 1.0;
+            }
+
+            a - k * TAU
+        };
+
+        let mut f_low = f(low);
+        let mut f_high = f(high);
+
+        while (high - low).abs() > CONVERGENCE {
+            let middle = low + (low - high) * f_low / (f_high - f_low);
+            let f_middle = f(middle);
+
+            if f_middle * f_high <= 0.0 {
+                low = high;
+                f_low = f_high;
+            } else {
+                f_low /= 2.0;
+            }
+
+            high = middle;
+            f_high = f_middle;
+        }
+
+        (low / 2.0).exp()
+    }
+}
+
+impl std::fmt::Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.0}{}",
+            self.rating,
+            if self.is_provisional() { "?" } else { "" }
+        )
+    }
+}
+
+fn ratings_key(server: &str, category: Category) -> String {
+    format!("ratings:{server}:{category}")
+}
+
+/// Updates the ratings of both players after a rated game, describing the changes.
+pub fn record(cache: &redis::Client, game: &Game, outcome: Outcome) -> Option<String> {
+    if !game.rated {
+        return None;
+    }
+
+    let category = Category::of(game.clock.as_ref());
+    let key = ratings_key(&game.server, category);
+
+    let result = cache.get_connection().and_then(|mut conn| {
+        let white = conn
+            .hget::<_, _, Option<RedisRating>>(&key, &game.white.id)?
+            .map(|RedisRating(rating)| rating)
+            .unwrap_or_default();
+        let black = conn
+            .hget::<_, _, Option<RedisRating>>(&key, &game.black.id)?
+            .map(|RedisRating(rating)| rating)
+            .unwrap_or_default();
+
+        let white_score = match outcome.winner {
+            Some(Color::White) => 1.0,
+            Some(Color::Black) => 0.0,
+            None => 0.5,
+        };
+        let new_white = white.update(black, white_score);
+        let new_black = black.update(white, 1.0 - white_score);
+
+        conn.hset_multiple::<_, _, _, ()>(
+            &key,
+            &[
+                (&game.white.id, RedisRating(new_white)),
+                (&game.black.id, RedisRating(new_black)),
+            ],
+        )?;
+
+        Ok([
+            (&game.white, white, new_white),
+            (&game.black, black, new_black),
+        ])
+    });
+
+    match result {
+        Ok(changes) => Some(format!(
+            "Rated {category}: {}",
+            changes
+                .iter()
+                .map(|(user, old, new)| format!(
+                    "<@{}> {new} ({:+.0})",
+                    user.id,
+                    new.rating - old.rating
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        Err(error) => {
+            dbg!(&format!(
+                "Failed to update ratings for game {}: {error}",
+                game.id
+            ));
+
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating {
+            rating,
+            deviation,
+            ..Rating::default()
+        }
+    }
+
+    /// The example from Glickman's "Example of the Glicko-2 system".
+    #[test]
+    fn matches_the_glicko2_example() {
+        let updated = rating(1500.0, 200.0).update_period(&[
+            (rating(1400.0, 30.0), 1.0),
+            (rating(1550.0, 100.0), 0.0),
+            (rating(1700.0, 300.0), 0.0),
+        ]);
+
+        assert!((updated.rating - 1464.06).abs() < 0.05, "{updated:?}");
+        assert!((updated.deviation - 151.52).abs() < 0.05, "{updated:?}");
+        assert!(
+            (updated.volatility - 0.059_99).abs() < 0.000_01,
+            "{updated:?}"
+        );
+        assert_eq!(updated.games, 3);
+    }
+
+    #[test]
+    fn single_games_are_one_game_periods() {
+        let player = rating(1600.0, 120.0);
+        let opponent = rating(1500.0, 80.0);
+
+        assert_eq!(
+            player.update(opponent, 0.5),
+            player.update_period(&[(opponent, 0.5)])
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chess::{Board, ChessMove, Color};
+use reywen::{
+    client::methods::message::{DataEditMessage, DataMessageSend},
+    structures::channels::message::{Message, Reply},
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    commands::{Error, PREFIX},
+    render::{text, BoardView},
+    Client,
+};
+
+use super::{chess960::Chess960, fen, pgn::ParsedPgn, san};
+
+/// Replays nobody looked at for this long are dropped.
+const REPLAY_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// A game being stepped through in a single message.
+pub struct Replay {
+    title: String,
+    positions: Vec<Board>,
+    moves: Vec<ChessMove>,
+    sans: Vec<String>,
+    ply: usize,
+    /// Plies played before the first move, for games starting from a FEN.
+    first_ply: usize,
+    message_id: String,
+    last_used: Instant,
+}
+
+impl Replay {
+    fn new(parsed: &ParsedPgn) -> Self {
+        let mut positions = vec![parsed.start];
+        let mut sans = Vec::new();
+        let mut chess960 = parsed.chess960;
+
+        for chess_move in &parsed.moves {
+            let board = positions[positions.len() - 1];
+
+            match &mut chess960 {
+                Some(chess960) => {
+                    sans.push(chess960.format(&board, *chess_move));
+                    positions.push(chess960.make_move(&board, *chess_move));
+                }
+                None => {
+                    sans.push(san::format(&board, *chess_move));
+                    positions.push(board.make_move_new(*chess_move));
+                }
+            }
+        }
+
+        Self {
+            title: format!(
+                "**{} vs {}** {}",
+                parsed.tag("White").unwrap_or("?"),
+                parsed.tag("Black").unwrap_or("?"),
+                parsed.tag("Result").unwrap_or("*")
+            ),
+            positions,
+            moves: parsed.moves.clone(),
+            sans,
+            ply: 0,
+            first_ply: parsed
+                .tag("FEN")
+                .and_then(|fen| {
+                    if parsed.chess960.is_some() {
+                        Chess960::from_fen(fen).ok().map(|(_, position)| position)
+                    } else {
+                        fen::parse(fen).ok()
+                    }
+                })
+                .map_or(0, |position| {
+                    (usize::try_from(position.fullmove_number).unwrap_or(1) - 1) * 2
+                        + usize::from(position.board.side_to_move() == Color::Black)
+                }),
+            message_id: String::new(),
+            last_used: Instant::now(),
+        }
+    }
+
+    fn content(&self) -> String {
+        let total = self.moves.len();
+        let board = &self.positions[self.ply];
+
+        let position = if self.ply == 0 {
+            format!("Starting position (0/{total})")
+        } else {
+            // The side to move now is the opposite of the side that played the last move.
+            let dots = if board.side_to_move() == Color::White {
+                "..."
+            } else {
+                "."
+            };
+
+            format!(
+                "Move {}{dots} {} ({}/{total})",
+                (self.first_ply + self.ply + 1) / 2,
+                self.sans[self.ply - 1],
+                self.ply
+            )
+        };
+
+        format!(
+            "{}\n{position}\n{}\nUse `{PREFIX}next`, `{PREFIX}prev` or `{PREFIX}goto <ply>` to move through the game.",
+            self.title,
+            text::render(&BoardView {
+                board,
+                last_move: self.ply.checked_sub(1).map(|ply| self.moves[ply]),
+                orientation: Color::White,
+            })
+        )
+    }
+}
+
+/// Keeps one replay per user and channel.
+#[derive(Clone, Default)]
+pub struct ReplayManager {
+    replays: Arc<Mutex<HashMap<(String, String), Replay>>>,
+}
+
+impl ReplayManager {
+    pub async fn start(
+        &self,
+        client: &Client,
+        message: &Message,
+        parsed: &ParsedPgn,
+    ) -> Result<(), Error> {
+        let mut replay = Replay::new(parsed);
+
+        let sent = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&replay.content())
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: false,
+                    }]),
+            )
+            .await
+            .map_err(|error| Error::Generic(format!("Failed to send replay: {error:?}")))?;
+
+        replay.message_id = sent.id;
+
+        let mut replays = self.replays.lock().await;
+        replays.retain(|_, replay| replay.last_used.elapsed() < REPLAY_LIFETIME);
+        replays.insert((message.channel.clone(), message.author.clone()), replay);
+
+        Ok(())
+    }
+
+    /// Moves the author's replay to the ply chosen from the current ply and the number of moves.
+    pub async fn navigate(
+        &self,
+        client: &Client,
+        message: &Message,
+        target: impl FnOnce(usize, usize) -> usize + Send,
+    ) -> Result<(), Error> {
+        let mut replays = self.replays.lock().await;
+
+        let Some(replay) = replays.get_mut(&(message.channel.clone(), message.author.clone()))
+        else {
+            return Err(Error::Generic(format!(
+                "You are not replaying a game in this channel, start one with `{PREFIX}replay`."
+            )));
+        };
+
+        replay.ply = target(replay.ply, replay.moves.len()).min(replay.moves.len());
+        replay.last_used = Instant::now();
+
+        let content = replay.content();
+        let message_id = replay.message_id.clone();
+        drop(replays);
+
+        let _ = client
+            .driver
+            .message_edit(
+                &message.channel,
+                &message_id,
+                &DataEditMessage::new().set_content(&content),
+            )
+            .await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use chess::{
+    get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks, get_pawn_quiets,
+    get_rook_moves, BitBoard, Board, ChessMove, Color, MoveGen, Piece, Square, EMPTY,
+};
+
+const LIGHT_SQUARES: BitBoard = BitBoard(0x55AA_55AA_55AA_55AA);
+
+/// The reason a move was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMove {
+    NoPiece(Square),
+    NotYourPiece(Square),
+    MissingPromotion,
+    UnexpectedPromotion,
+    CannotCastle,
+    LeavesKingInCheck,
+    Illegal,
+}
+
+impl std::fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPiece(square) => write!(f, "There is no piece on {square}."),
+            Self::NotYourPiece(square) => write!(f, "The piece on {square} is not yours."),
+            Self::MissingPromotion => f.write_str(
+                "A pawn reaching the last rank has to promote, add the piece to promote to (e.g. `e8=Q` or `e7e8q`).",
+            ),
+            Self::UnexpectedPromotion => {
+                f.write_str("Only pawns reaching the last rank can promote.")
+            }
+            Self::CannotCastle => f.write_str("You cannot castle right now."),
+            Self::LeavesKingInCheck => f.write_str("That move would leave your king in check."),
+            Self::Illegal => f.write_str("That move is not legal."),
+        }
+    }
+}
+
+pub fn is_legal(board: &Board, chess_move: ChessMove) -> bool {
+    MoveGen::new_legal(board).any(|legal| legal == chess_move)
+}
+
+/// Checks a move against the legal moves of the position, explaining why it is illegal if it is.
+pub fn check_move(board: &Board, chess_move: ChessMove) -> Result<(), IllegalMove> {
+    if is_legal(board, chess_move) {
+        return Ok(());
+    }
+
+    let source = chess_move.get_source();
+    let dest = chess_move.get_dest();
+    let color = board.side_to_move();
+
+    let Some(piece) = board.piece_on(source) else {
+        return Err(IllegalMove::NoPiece(source));
+    };
+
+    if board.color_on(source) != Some(color) {
+        return Err(IllegalMove::NotYourPiece(source));
+    }
+
+    if piece == Piece::Pawn && dest.get_rank() == color.to_their_backrank() {
+        if chess_move.get_promotion().is_none()
+            && is_legal(board, ChessMove::new(source, dest, Some(Piece::Queen)))
+        {
+            return Err(IllegalMove::MissingPromotion);
+        }
+    } else if chess_move.get_promotion().is_some() {
+        return Err(IllegalMove::UnexpectedPromotion);
+    }
+
+    if piece == Piece::King
+        && source.get_rank() == dest.get_rank()
+        && source
+            .get_file()
+            .to_index()
+            .abs_diff(dest.get_file().to_index())
+            == 2
+    {
+        return Err(IllegalMove::CannotCastle);
+    }
+
+    if is_pseudo_legal(board, chess_move) {
+        Err(IllegalMove::LeavesKingInCheck)
+    } else {
+        Err(IllegalMove::Illegal)
+    }
+}
+
+/// Whether neither side has enough material left to ever checkmate.
+pub fn is_insufficient_material(board: &Board) -> bool {
+    if *board.pieces(Piece::Pawn) | *board.pieces(Piece::Rook) | *board.pieces(Piece::Queen)
+        != EMPTY
+    {
+        return false;
+    }
+
+    let knights = board.pieces(Piece::Knight).popcnt();
+    let bishops = *board.pieces(Piece::Bishop);
+
+    if knights + bishops.popcnt() <= 1 {
+        return true;
+    }
+
+    // Any number of bishops that all stand on the same square color cannot mate.
+    knights == 0 && (bishops & LIGHT_SQUARES == bishops || bishops & LIGHT_SQUARES == EMPTY)
+}
+
+/// Whether the player has anything besides a lone minor piece to mate with.
+pub fn has_mating_material(board: &Board, color: Color) -> bool {
+    let pieces = *board.color_combined(color);
+
+    (*board.pieces(Piece::Pawn) | *board.pieces(Piece::Rook) | *board.pieces(Piece::Queen)) & pieces
+        != EMPTY
+        || ((*board.pieces(Piece::Knight) | *board.pieces(Piece::Bishop)) & pieces).popcnt() >= 2
+}
+
+/// Whether the piece can reach the target square, ignoring whether the king is left in check.
+fn is_pseudo_legal(board: &Board, chess_move: ChessMove) -> bool {
+    let source = chess_move.get_source();
+    let color = board.side_to_move();
+    let blockers = *board.combined();
+
+    let targets = match board.piece_on(source) {
+        Some(Piece::Pawn) => {
+            let en_passant = board.en_passant().map_or(EMPTY, |square| {
+                BitBoard::from_square(square.uforward(color))
+            });
+
+            get_pawn_quiets(source, color, blockers)
+                | get_pawn_attacks(source, color, *board.color_combined(!color) | en_passant)
+        }
+        Some(Piece::Knight) => get_knight_moves(source),
+        Some(Piece::Bishop) => get_bishop_moves(source, blockers),
+        Some(Piece::Rook) => get_rook_moves(source, blockers),
+        Some(Piece::Queen) => get_bishop_moves(source, blockers) | get_rook_moves(source, blockers),
+        Some(Piece::King) => get_king_moves(source),
+        None => EMPTY,
+    };
+
+    targets & !*board.color_combined(color) & BitBoard::from_square(chess_move.get_dest()) != EMPTY
+}
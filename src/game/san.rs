@@ -0,0 +1,389 @@
+use std::str::FromStr;
+
+use chess::{Board, BoardStatus, ChessMove, Color, File, MoveGen, Piece, Rank, Square, EMPTY};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::rules::{self, IllegalMove};
+
+static SAN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:(?P<castle>[O0]-[O0](?:-[O0])?)|(?P<piece>[NBRQK])?(?P<file>[a-h])?(?P<rank>[1-8])?x?(?P<dest>[a-h][1-8])(?:=?(?P<promotion>[NBRQnbrq]))?)[+#]?[!?]*$",
+    )
+    .unwrap()
+});
+
+static UCI_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^([a-h][1-8])([a-h][1-8])([rnbq])?$").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Syntax(String),
+    Ambiguous(Vec<Square>),
+    Illegal(IllegalMove),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax(text) => write!(
+                f,
+                "`{text}` is not a valid move, use algebraic notation (e.g. `Nf3`, `exd5`, `O-O`, `e8=Q`) or UCI (e.g. `g1f3`)."
+            ),
+            Self::Ambiguous(sources) => write!(
+                f,
+                "That move is ambiguous, it could be played from {}.",
+                sources
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Illegal(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// Parses a move written in standard algebraic notation, falling back to UCI.
+pub fn parse_move(board: &Board, text: &str) -> Result<ChessMove, ParseError> {
+    match parse_san(board, text) {
+        Ok(chess_move) => Ok(chess_move),
+        Err(_) if UCI_REGEX.is_match(text) => parse_uci(board, text),
+        Err(error) => Err(error),
+    }
+}
+
+pub fn parse_san(board: &Board, text: &str) -> Result<ChessMove, ParseError> {
+    let Some(captures) = SAN_REGEX.captures(text) else {
+        return Err(ParseError::Syntax(text.to_string()));
+    };
+
+    if let Some(castle) = captures.name("castle") {
+        let king = board.king_square(board.side_to_move());
+        let file = if castle.as_str().len() > 3 {
+            File::C
+        } else {
+            File::G
+        };
+        let castle = ChessMove::new(king, Square::make_square(king.get_rank(), file), None);
+
+        return rules::check_move(board, castle)
+            .map(|()| castle)
+            .map_err(ParseError::Illegal);
+    }
+
+    let piece = captures
+        .name("piece")
+        .and_then(|piece| piece_from_str(piece.as_str()))
+        .unwrap_or(Piece::Pawn);
+    let file = captures
+        .name("file")
+        .map(|file| File::from_index(usize::from(file.as_str().as_bytes()[0] - b'a')));
+    let rank = captures
+        .name("rank")
+        .map(|rank| Rank::from_index(usize::from(rank.as_str().as_bytes()[0] - b'1')));
+    let promotion = captures
+        .name("promotion")
+        .and_then(|promotion| piece_from_str(promotion.as_str()));
+
+    let Some(Ok(dest)) = captures
+        .name("dest")
+        .map(|dest| Square::from_str(dest.as_str()))
+    else {
+        return Err(ParseError::Syntax(text.to_string()));
+    };
+
+    let matches_source = |square: Square| {
+        file.is_none_or(|file| square.get_file() == file)
+            && rank.is_none_or(|rank| square.get_rank() == rank)
+    };
+
+    let candidates = MoveGen::new_legal(board)
+        .filter(|chess_move| {
+            chess_move.get_dest() == dest
+                && chess_move.get_promotion() == promotion
+                && board.piece_on(chess_move.get_source()) == Some(piece)
+                && matches_source(chess_move.get_source())
+        })
+        .collect::<Vec<_>>();
+
+    match candidates.as_slice() {
+        [chess_move] => Ok(*chess_move),
+        [] => {
+            let sources = *board.pieces(piece) & *board.color_combined(board.side_to_move());
+            let reasons = sources
+                .filter(|source| matches_source(*source))
+                .filter_map(|source| {
+                    rules::check_move(board, ChessMove::new(source, dest, promotion)).err()
+                })
+                .collect::<Vec<_>>();
+
+            Err(ParseError::Illegal(
+                [
+                    IllegalMove::LeavesKingInCheck,
+                    IllegalMove::MissingPromotion,
+                    IllegalMove::UnexpectedPromotion,
+                ]
+                .into_iter()
+                .find(|reason| reasons.contains(reason))
+                .unwrap_or(IllegalMove::Illegal),
+            ))
+        }
+        _ => Err(ParseError::Ambiguous(
+            candidates.iter().map(ChessMove::get_source).collect(),
+        )),
+    }
+}
+
+pub fn parse_uci(board: &Board, text: &str) -> Result<ChessMove, ParseError> {
+    let Some(chess_move) = parse_uci_syntax(text) else {
+        return Err(ParseError::Syntax(text.to_string()));
+    };
+
+    rules::check_move(board, chess_move)
+        .map(|()| chess_move)
+        .map_err(ParseError::Illegal)
+}
+
+/// Reads a move in UCI notation without checking whether it can be played.
+pub fn parse_uci_syntax(text: &str) -> Option<ChessMove> {
+    let captures = UCI_REGEX.captures(text)?;
+
+    let (Some(Ok(source)), Some(Ok(dest))) = (
+        captures
+            .get(1)
+            .map(|source| Square::from_str(source.as_str())),
+        captures.get(2).map(|dest| Square::from_str(dest.as_str())),
+    ) else {
+        return None;
+    };
+
+    let promotion = captures
+        .get(3)
+        .and_then(|promotion| piece_from_str(promotion.as_str()));
+
+    Some(ChessMove::new(source, dest, promotion))
+}
+
+fn piece_from_str(piece: &str) -> Option<Piece> {
+    match piece {
+        "N" | "n" => Some(Piece::Knight),
+        "B" | "b" => Some(Piece::Bishop),
+        "R" | "r" => Some(Piece::Rook),
+        "Q" | "q" => Some(Piece::Queen),
+        "K" | "k" => Some(Piece::King),
+        _ => None,
+    }
+}
+
+/// Writes a legal move in standard algebraic notation.
+pub fn format(board: &Board, chess_move: ChessMove) -> String {
+    let source = chess_move.get_source();
+    let dest = chess_move.get_dest();
+    let piece = board.piece_on(source).unwrap_or(Piece::Pawn);
+
+    let mut text = if piece == Piece::King
+        && source
+            .get_file()
+            .to_index()
+            .abs_diff(dest.get_file().to_index())
+            == 2
+    {
+        if dest.get_file() == File::G {
+            String::from("O-O")
+        } else {
+            String::from("O-O-O")
+        }
+    } else {
+        let is_capture = board.piece_on(dest).is_some()
+            || (piece == Piece::Pawn && source.get_file() != dest.get_file());
+        let mut text = String::new();
+
+        if piece == Piece::Pawn {
+            if is_capture {
+                text.push_str(&source.to_string()[..1]);
+            }
+        } else {
+            text.push_str(&piece.to_string(Color::White));
+
+            let others = MoveGen::new_legal(board)
+                .filter(|other| {
+                    other.get_dest() == dest
+                        && other.get_source() != source
+                        && board.piece_on(other.get_source()) == Some(piece)
+                })
+                .map(|other| other.get_source())
+                .collect::<Vec<_>>();
+
+            if !others.is_empty() {
+                let source_name = source.to_string();
+
+                if others
+                    .iter()
+                    .all(|other| other.get_file() != source.get_file())
+                {
+                    text.push_str(&source_name[..1]);
+                } else if others
+                    .iter()
+                    .all(|other| other.get_rank() != source.get_rank())
+                {
+                    text.push_str(&source_name[1..]);
+                } else {
+                    text.push_str(&source_name);
+                }
+            }
+        }
+
+        if is_capture {
+            text.push('x');
+        }
+
+        text.push_str(&dest.to_string());
+
+        if let Some(promotion) = chess_move.get_promotion() {
+            text.push('=');
+            text.push_str(&promotion.to_string(Color::White));
+        }
+
+        text
+    };
+
+    text.push_str(check_suffix(&board.make_move_new(chess_move)));
+
+    text
+}
+
+/// The `+` or `#` to add to a move that led to the position.
+pub fn check_suffix(after: &Board) -> &'static str {
+    if after.status() == BoardStatus::Checkmate {
+        "#"
+    } else if *after.checkers() == EMPTY {
+        ""
+    } else {
+        "+"
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const CASTLING: &str = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    const KNIGHTS: &str = "4k3/8/8/8/8/8/8/1N1K1N2 w - - 0 1";
+    const ROOKS: &str = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    const PROMOTION: &str = "8/4P3/8/8/8/8/k7/4K3 w - - 0 1";
+    const PINNED: &str = "4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1";
+
+    fn parse(fen: &str, text: &str) -> Result<String, ParseError> {
+        parse_move(&Board::from_str(fen).unwrap(), text).map(|chess_move| chess_move.to_string())
+    }
+
+    #[test]
+    fn parses_legal_moves() {
+        let cases = [
+            (START, "e4", "e2e4"),
+            (START, "Nf3", "g1f3"),
+            (START, "Nf3!?", "g1f3"),
+            (CASTLING, "O-O", "e1g1"),
+            (CASTLING, "0-0", "e1g1"),
+            (CASTLING, "O-O-O", "e1c1"),
+            (CASTLING, "0-0-0+", "e1c1"),
+            (KNIGHTS, "Nbd2", "b1d2"),
+            (KNIGHTS, "Nfd2", "f1d2"),
+            (KNIGHTS, "Nf1d2", "f1d2"),
+            (ROOKS, "R1a3", "a1a3"),
+            (ROOKS, "R5a3", "a5a3"),
+            (PROMOTION, "e8=Q", "e7e8q"),
+            (PROMOTION, "e8Q", "e7e8q"),
+            (PROMOTION, "e8=N", "e7e8n"),
+            // UCI is the fallback when the text is not algebraic notation.
+            (START, "g1f3", "g1f3"),
+            (START, "e2e4", "e2e4"),
+            (PROMOTION, "e7e8r", "e7e8r"),
+        ];
+
+        for (fen, text, expected) in cases {
+            assert_eq!(
+                parse(fen, text),
+                Ok(expected.to_string()),
+                "{text} in {fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn explains_rejected_moves() {
+        let cases = [
+            (START, "hello", ParseError::Syntax(String::from("hello"))),
+            (START, "e2e9", ParseError::Syntax(String::from("e2e9"))),
+            (START, "Nf6", ParseError::Illegal(IllegalMove::Illegal)),
+            (START, "O-O", ParseError::Illegal(IllegalMove::CannotCastle)),
+            (
+                START,
+                "e4=Q",
+                ParseError::Illegal(IllegalMove::UnexpectedPromotion),
+            ),
+            (
+                PROMOTION,
+                "e8",
+                ParseError::Illegal(IllegalMove::MissingPromotion),
+            ),
+            (
+                PROMOTION,
+                "e7e8",
+                ParseError::Illegal(IllegalMove::MissingPromotion),
+            ),
+            (
+                PINNED,
+                "Bd3",
+                ParseError::Illegal(IllegalMove::LeavesKingInCheck),
+            ),
+            (
+                START,
+                "e3e4",
+                ParseError::Illegal(IllegalMove::NoPiece(Square::E3)),
+            ),
+        ];
+
+        for (fen, text, expected) in cases {
+            assert_eq!(parse(fen, text), Err(expected), "{text} in {fen}");
+        }
+    }
+
+    #[test]
+    fn reports_every_source_of_ambiguous_moves() {
+        let cases = [
+            (KNIGHTS, "Nd2", [Square::B1, Square::F1]),
+            (ROOKS, "Ra3", [Square::A1, Square::A5]),
+        ];
+
+        for (fen, text, expected) in cases {
+            let Err(ParseError::Ambiguous(mut sources)) = parse(fen, text) else {
+                panic!("{text} in {fen} is not ambiguous");
+            };
+            sources.sort();
+
+            assert_eq!(sources, expected, "{text} in {fen}");
+        }
+    }
+
+    #[test]
+    fn formats_moves() {
+        let cases = [
+            (START, "g1f3", "Nf3"),
+            (CASTLING, "e1c1", "O-O-O"),
+            (KNIGHTS, "b1d2", "Nbd2"),
+            (ROOKS, "a5a3", "R5a3"),
+            (PROMOTION, "e7e8q", "e8=Q"),
+        ];
+
+        for (fen, text, expected) in cases {
+            let board = Board::from_str(fen).unwrap();
+            let chess_move = parse_uci(&board, text).unwrap();
+
+            assert_eq!(format(&board, chess_move), expected, "{text} in {fen}");
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, VecDeque};
+
+use chess::Color;
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+use crate::{RedisProfile, RedisTally};
+
+use super::{outcome::Outcome, persistence::ColorDef, Game};
+
+/// The scope of the leaderboard counting games from every server.
+pub const GLOBAL: &str = "global";
+
+const PROFILES_KEY: &str = "profiles";
+
+/// How many of the latest games a profile remembers.
+const RECENT_GAMES: usize = 5;
+
+/// How a game ended for one of the players.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl GameResult {
+    fn of(outcome: Outcome, color: Color) -> Self {
+        match outcome.winner {
+            Some(winner) if winner == color => Self::Win,
+            Some(_) => Self::Loss,
+            None => Self::Draw,
+        }
+    }
+
+    pub const fn letter(self) -> char {
+        match self {
+            Self::Win => 'W',
+            Self::Loss => 'L',
+            Self::Draw => 'D',
+        }
+    }
+}
+
+/// A player's results in a server, or across all of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Tally {
+    pub const fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// The score in half points, a win being worth two and a draw one.
+    pub const fn half_points(&self) -> u32 {
+        self.wins * 2 + self.draws
+    }
+
+    /// The score written with a ½ for an odd number of draws.
+    pub fn score(&self) -> String {
+        let half_points = self.half_points();
+
+        match (half_points / 2, half_points % 2) {
+            (0, 1) => String::from("½"),
+            (points, 1) => format!("{points}½"),
+            (points, _) => points.to_string(),
+        }
+    }
+
+    /// The percentage of games won, rounded down.
+    pub const fn win_rate(&self) -> u32 {
+        match self.games() {
+            0 => 0,
+            games => self.wins * 100 / games,
+        }
+    }
+
+    fn add(&mut self, result: GameResult) {
+        match result {
+            GameResult::Win => self.wins += 1,
+            GameResult::Loss => self.losses += 1,
+            GameResult::Draw => self.draws += 1,
+        }
+    }
+}
+
+/// A finished game as remembered in a profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentGame {
+    pub opponent: String,
+    #[serde(with = "ColorDef")]
+    pub color: Color,
+    pub result: GameResult,
+}
+
+/// Everything the profile of a player shows, gathered from all servers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub white: Tally,
+    pub black: Tally,
+    /// The result of the latest games and how many times in a row it happened.
+    pub streak: Option<(GameResult, u32)>,
+    /// How often each first move was played, in algebraic notation.
+    pub first_moves: HashMap<String, u32>,
+    /// The number of moves of every game together, counting a move for each side as one.
+    pub total_moves: u64,
+    /// The latest games, newest first.
+    pub recent: VecDeque<RecentGame>,
+}
+
+impl Profile {
+    pub const fn games(&self) -> u32 {
+        self.white.games() + self.black.games()
+    }
+
+    /// The first moves played most often, most played first.
+    pub fn favourite_first_moves(&self, count: usize) -> Vec<(&str, u32)> {
+        let mut moves = self
+            .first_moves
+            .iter()
+            .map(|(san, times)| (san.as_str(), *times))
+            .collect::<Vec<_>>();
+        moves.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        moves.truncate(count);
+
+        moves
+    }
+
+    /// The average number of moves per game.
+    pub fn average_length(&self) -> Option<u64> {
+        self.total_moves.checked_div(u64::from(self.games()))
+    }
+
+    fn add(&mut self, game: &Game, color: Color, result: GameResult) {
+        match color {
+            Color::White => self.white.add(result),
+            Color::Black => self.black.add(result),
+        }
+
+        self.streak = match self.streak {
+            Some((streak, length)) if streak == result => Some((result, length + 1)),
+            _ => Some((result, 1)),
+        };
+
+        // The first move of a game started from a position might not be the player's.
+        if let Some(played) = game
+            .moves
+            .iter()
+            .zip([game.starting_side(), !game.starting_side()])
+            .find(|(_, mover)| *mover == color)
+            .map(|(played, _)| played)
+        {
+            *self.first_moves.entry(played.san.clone()).or_default() += 1;
+        }
+
+        self.total_moves += u64::try_from(game.moves.len().div_ceil(2)).unwrap_or_default();
+
+        self.recent.push_front(RecentGame {
+            opponent: game.player(!color).id.clone(),
+            color,
+            result,
+        });
+        self.recent.truncate(RECENT_GAMES);
+    }
+}
+
+fn results_key(scope: &str) -> String {
+    format!("results:{scope}")
+}
+
+fn leaderboard_key(scope: &str) -> String {
+    format!("leaderboard:{scope}")
+}
+
+/// Adds the result of a finished game to the players' tallies in its server and globally, and to
+/// their profiles.
+///
+/// The bot has no profile, and games against it only count towards profiles so nobody can climb
+/// the leaderboards by beating it. Games against yourself are not counted.
+pub fn record(cache: &redis::Client, game: &Game, outcome: Outcome, bot_id: &str) {
+    if game.white.id == game.black.id {
+        return;
+    }
+
+    let is_ranked = !game.is_player(bot_id);
+
+    let result = cache.get_connection().and_then(|mut conn| {
+        for color in [Color::White, Color::Black] {
+            let player = &game.player(color).id;
+
+            if player == bot_id {
+                continue;
+            }
+
+            let result = GameResult::of(outcome, color);
+
+            for scope in [game.server.as_str(), GLOBAL]
+                .into_iter()
+                .filter(|_| is_ranked)
+            {
+                let mut tally = conn
+                    .hget::<_, _, Option<RedisTally>>(results_key(scope), player)?
+                    .map(|RedisTally(tally)| tally)
+                    .unwrap_or_default();
+                tally.add(result);
+
+                conn.hset::<_, _, _, ()>(results_key(scope), player, RedisTally(tally))?;
+                conn.zadd::<_, _, _, ()>(leaderboard_key(scope), player, tally.half_points())?;
+            }
+
+            let mut profile = conn
+                .hget::<_, _, Option<RedisProfile>>(PROFILES_KEY, player)?
+                .map(|RedisProfile(profile)| profile)
+                .unwrap_or_default();
+            profile.add(game, color, result);
+
+            conn.hset::<_, _, _, ()>(PROFILES_KEY, player, RedisProfile(profile))?;
+        }
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        dbg!(&format!(
+            "Failed to record results of game {}: {error}",
+            game.id
+        ));
+    }
+}
+
+/// A page of the leaderboard, best first, and the number of ranked players.
+pub fn leaderboard(
+    cache: &redis::Client,
+    scope: &str,
+    page: usize,
+    page_size: usize,
+) -> crate::Result<(Vec<(String, Tally)>, usize)> {
+    let mut conn = cache.get_connection()?;
+
+    let total: usize = conn.zcard(leaderboard_key(scope))?;
+    let start = page.saturating_mul(page_size);
+    let players: Vec<String> = conn.zrevrange(
+        leaderboard_key(scope),
+        isize::try_from(start).unwrap_or(isize::MAX),
+        isize::try_from(start.saturating_add(page_size)).unwrap_or(isize::MAX) - 1,
+    )?;
+
+    let mut entries = Vec::with_capacity(players.len());
+
+    for player in players {
+        let tally = conn
+            .hget::<_, _, Option<RedisTally>>(results_key(scope), &player)?
+            .map(|RedisTally(tally)| tally)
+            .unwrap_or_default();
+
+        entries.push((player, tally));
+    }
+
+    Ok((entries, total))
+}
+
+pub fn profile(cache: &redis::Client, user_id: &str) -> crate::Result<Profile> {
+    let mut conn = cache.get_connection()?;
+    let profile: Option<RedisProfile> = conn.hget(PROFILES_KEY, user_id)?;
+
+    Ok(profile
+        .map(|RedisProfile(profile)| profile)
+        .unwrap_or_default())
+}
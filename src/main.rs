@@ -8,7 +8,18 @@
 )]
 
 mod commands;
-
+mod engine;
+mod game;
+mod puzzle;
+mod render;
+
+use game::{
+    archive::ArchivedGame,
+    persistence::SavedGame,
+    rating::Rating,
+    stats::{Profile, Tally},
+};
+use puzzle::{daily::DailyPuzzle, stats::PuzzleStats};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -43,14 +54,21 @@ async fn main() {
 #[derive(Clone)]
 pub struct Client {
     driver: reywen::client::Client,
+    token: String,
+    is_bot: bool,
     user: User,
     cache: redis::Client,
+    games: game::GameManager,
+    engine: engine::Engine,
+    replays: game::replay::ReplayManager,
+    puzzles: puzzle::PuzzleManager,
 }
 
 #[derive(Debug)]
 pub enum Error {
     Delta(DeltaError),
     Redis(RedisError),
+    Http(reqwest::Error),
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +78,7 @@ pub enum ResourceType {
     Channel,
     Member,
     Emoji,
+    Game,
 }
 
 impl From<DeltaError> for Error {
@@ -74,6 +93,12 @@ impl From<RedisError> for Error {
     }
 }
 
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 macro_rules! redis_json_wrapper {
@@ -106,6 +131,15 @@ redis_json_wrapper!(RedisUser, User);
 redis_json_wrapper!(RedisServer, Server);
 redis_json_wrapper!(RedisChannel, Channel);
 redis_json_wrapper!(RedisEmoji, Emoji);
+redis_json_wrapper!(RedisSavedGame, SavedGame);
+redis_json_wrapper!(RedisRating, Rating);
+redis_json_wrapper!(RedisTally, Tally);
+redis_json_wrapper!(RedisProfile, Profile);
+redis_json_wrapper!(RedisArchivedGame, ArchivedGame);
+redis_json_wrapper!(RedisPuzzleStats, PuzzleStats);
+redis_json_wrapper!(RedisDailyPuzzle, DailyPuzzle);
+
+const AUTUMN_URL: &str = "https://autumn.revolt.chat";
 
 const ULID_REGEX_STR: &str = "[0-7][0-9A-HJKMNP-TV-Z]{25}";
 
@@ -115,8 +149,33 @@ static ULID_REGEX: once_cell::sync::Lazy<Regex> =
 static ULID_MENTION_REGEX: once_cell::sync::Lazy<Regex> =
     Lazy::new(|| Regex::new(&format!("^<@({ULID_REGEX_STR})>$")).unwrap());
 
+static CHANNEL_MENTION_REGEX: once_cell::sync::Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!("^<#({ULID_REGEX_STR})>$")).unwrap());
+
 impl Client {
     pub async fn run(&self) {
+        self.games.restore(self).await;
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
+
+            loop {
+                interval.tick().await;
+                this.games.tick(&this).await;
+            }
+        });
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+
+            loop {
+                interval.tick().await;
+                this.puzzles.tick_daily(&this).await;
+            }
+        });
+
         loop {
             let (mut read, write) = self.driver.websocket.dual_async().await;
 
@@ -176,7 +235,17 @@ impl Client {
                     WebSocketEvent::Message { message } => {
                         let this = self.clone();
                         tokio::spawn(async move {
-                            if let Err(error) = commands::handle_command(&this, &message).await {
+                            let handled = match this.puzzles.handle_message(&this, &message).await {
+                                Ok(false) => this.games.handle_message(&this, &message).await,
+                                handled => handled,
+                            };
+                            let result = match handled {
+                                Ok(true) => Ok(()),
+                                Ok(false) => commands::handle_command(&this, &message).await,
+                                Err(error) => Err(error),
+                            };
+
+                            if let Err(error) = result {
                                 let _ = this
                                     .driver
                                     .message_send(
@@ -204,8 +273,14 @@ impl Client {
 
         let mut this = Self {
             driver,
+            token: token.to_string(),
+            is_bot,
             user: User::default(),
             cache: redis::Client::open("redis://127.0.0.1/").expect("Failed to connect to Redis DB"),
+            games: game::GameManager::default(),
+            engine: engine::Engine::default(),
+            replays: game::replay::ReplayManager::default(),
+            puzzles: puzzle::PuzzleManager::default(),
         };
 
         this.user = this.fetch_user("@me").await.expect("Could not fetch bot");
@@ -228,6 +303,47 @@ impl Client {
         Ok(())
     }
 
+    /// Uploads a file to Autumn, returning the attachment ID to send it with.
+    async fn upload_attachment(&self, filename: &str, data: Vec<u8>) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Uploaded {
+            id: String,
+        }
+
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(data).file_name(filename.to_string()),
+        );
+
+        let Uploaded { id } = reqwest::Client::new()
+            .post(format!("{AUTUMN_URL}/attachments"))
+            .header(
+                if self.is_bot {
+                    "x-bot-token"
+                } else {
+                    "x-session-token"
+                },
+                &self.token,
+            )
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Downloads a text attachment from Autumn.
+    async fn download_attachment(&self, tag: &str, id: &str) -> Result<String> {
+        Ok(reqwest::get(format!("{AUTUMN_URL}/{tag}/{id}"))
+            .await?
+            .error_for_status()?
+            .text()
+            .await?)
+    }
+
     async fn resolve_user(&self, haystack: &str) -> Result<Option<User>> {
         if let Some(Some(ulid)) = ULID_REGEX
             .captures(haystack)
@@ -243,6 +359,18 @@ impl Client {
         }
     }
 
+    async fn resolve_channel(&self, haystack: &str) -> Result<Option<Channel>> {
+        if let Some(Some(ulid)) = ULID_REGEX
+            .captures(haystack)
+            .or_else(|| CHANNEL_MENTION_REGEX.captures(haystack))
+            .map(|captures| captures.get(1))
+        {
+            Ok(Some(self.fetch_channel(ulid.as_str()).await?))
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn fetch_user(&self, id: &str) -> Result<User> {
         let mut conn = self.cache.get_connection()?;
 
@@ -275,6 +403,14 @@ impl Client {
         }
     }
 
+    /// Gets the ID of the server a channel is in, or the channel's own ID outside of servers.
+    async fn fetch_server_id(&self, channel_id: &str) -> Result<String> {
+        Ok(match self.fetch_channel(channel_id).await? {
+            Channel::TextChannel { server, .. } => server,
+            channel => channel.id().to_string(),
+        })
+    }
+
     async fn fetch_server(&self, id: &str) -> Result<Server> {
         let mut conn = self.cache.get_connection()?;
 
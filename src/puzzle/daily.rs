@@ -0,0 +1,360 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chess::BoardStatus;
+use redis::Commands;
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::{
+        message::{Message, Reply},
+        Channel,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::{Error, PREFIX},
+    game::{outcome::color_name, pgn, san, send_position},
+    Client, RedisDailyPuzzle, ResourceType,
+};
+
+use super::{database, database::Puzzle, render_mode, ActivePuzzle, PuzzleManager};
+
+const DAILY_PUZZLES_KEY: &str = "daily-puzzles";
+
+/// The rating daily puzzles are picked around, so most of the server can take part.
+const DAILY_RATING: f64 = 1500.0;
+
+/// Where and when a server gets its daily puzzle, along with the last one posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyPuzzle {
+    pub channel: String,
+    /// The time of day to post at, in UTC.
+    pub hour: u8,
+    pub minute: u8,
+    pub current: Option<Posted>,
+}
+
+/// A posted daily puzzle and the answers it got.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Posted {
+    pub puzzle: Puzzle,
+    /// The day it was posted, counted from the Unix epoch.
+    pub day: u64,
+    pub solvers: Vec<String>,
+    /// Everyone who answered, whether they solved it or not.
+    pub answered: Vec<String>,
+}
+
+impl DailyPuzzle {
+    pub fn time(&self) -> String {
+        format!("{:02}:{:02}", self.hour, self.minute)
+    }
+
+    fn is_due(&self, day: u64, minute_of_day: u64) -> bool {
+        minute_of_day >= u64::from(self.hour) * 60 + u64::from(self.minute)
+            && self.current.as_ref().is_none_or(|posted| posted.day < day)
+    }
+}
+
+/// Parses a time of day written as `HH:MM`.
+pub fn parse_time(text: &str) -> Option<(u8, u8)> {
+    let (hour, minute) = text.split_once(':')?;
+    let hour = hour.parse::<u8>().ok().filter(|hour| *hour < 24)?;
+    let minute = minute.parse::<u8>().ok().filter(|minute| *minute < 60)?;
+
+    Some((hour, minute))
+}
+
+pub fn load(cache: &redis::Client, server: &str) -> crate::Result<Option<DailyPuzzle>> {
+    let mut conn = cache.get_connection()?;
+    let json: Option<String> = conn.hget(DAILY_PUZZLES_KEY, server)?;
+
+    Ok(json.and_then(|json| read(cache, server, &json)))
+}
+
+/// Reads a stored daily puzzle, dropping it if it cannot be read so the schedule keeps running.
+fn read(cache: &redis::Client, server: &str, json: &str) -> Option<DailyPuzzle> {
+    match serde_json::from_str(json) {
+        Ok(daily) => Some(daily),
+        Err(error) => {
+            dbg!(&format!(
+                "Dropping unreadable daily puzzle of {server}: {error}"
+            ));
+
+            if let Err(error) = remove(cache, server) {
+                dbg!(&format!(
+                    "Failed to remove the daily puzzle of {server}: {error:?}"
+                ));
+            }
+
+            None
+        }
+    }
+}
+
+pub fn save(cache: &redis::Client, server: &str, daily: DailyPuzzle) -> crate::Result<()> {
+    let mut conn = cache.get_connection()?;
+    conn.hset::<_, _, _, ()>(DAILY_PUZZLES_KEY, server, RedisDailyPuzzle(daily))?;
+
+    Ok(())
+}
+
+pub fn remove(cache: &redis::Client, server: &str) -> crate::Result<()> {
+    let mut conn = cache.get_connection()?;
+    conn.hdel::<_, _, ()>(DAILY_PUZZLES_KEY, server)?;
+
+    Ok(())
+}
+
+/// The current day since the Unix epoch and the minute of that day, in UTC.
+fn now() -> (u64, u64) {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    (seconds / 86400, seconds % 86400 / 60)
+}
+
+/// How many moves the solver has to find.
+fn solver_moves(puzzle: &Puzzle) -> usize {
+    puzzle.moves.len() / 2
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        "move"
+    } else {
+        "moves"
+    }
+}
+
+impl PuzzleManager {
+    /// Posts the puzzle of every server whose time has come, revealing the previous one.
+    pub async fn tick_daily(&self, client: &Client) {
+        let (day, minute_of_day) = now();
+
+        let dailies = match client
+            .cache
+            .get_connection()
+            .and_then(|mut conn| conn.hgetall::<_, HashMap<String, String>>(DAILY_PUZZLES_KEY))
+        {
+            Ok(dailies) => dailies,
+            Err(error) => {
+                dbg!(&format!("Failed to load daily puzzles: {error}"));
+
+                return;
+            }
+        };
+
+        for (server, json) in dailies {
+            let Some(daily) = read(&client.cache, &server, &json) else {
+                continue;
+            };
+
+            if daily.is_due(day, minute_of_day) {
+                if let Err(error) = self.publish_daily(client, &server, day).await {
+                    dbg!(&format!(
+                        "Failed to post the daily puzzle of {server}: {error}"
+                    ));
+                }
+            }
+        }
+    }
+
+    async fn publish_daily(&self, client: &Client, server: &str, day: u64) -> Result<(), Error> {
+        let _guard = self.daily.lock().await;
+
+        // The puzzle could have been stopped while waiting for the lock.
+        let Some(mut daily) = load(&client.cache, server).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?
+        else {
+            return Ok(());
+        };
+
+        let puzzles = self.puzzles().await?;
+        let puzzle = database::pick(puzzles, DAILY_RATING, None)
+            .ok_or_else(|| Error::Generic(String::from("There are no puzzles.")))?
+            .clone();
+        let (active, opening) = ActivePuzzle::start(puzzle)?;
+
+        if let Some(posted) = daily.current.take() {
+            let (previous, _) = ActivePuzzle::start(posted.puzzle)?;
+            let solvers = if posted.solvers.is_empty() {
+                String::from("Nobody solved it.")
+            } else {
+                format!(
+                    "Solved by {}.",
+                    posted
+                        .solvers
+                        .iter()
+                        .map(|solver| format!("<@{solver}>"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+
+            let _ = client
+                .driver
+                .message_send(
+                    &daily.channel,
+                    &DataMessageSend::new().set_content(&format!(
+                        "The solution to the last daily puzzle was {}\n{solvers}\n<{}>",
+                        previous.remaining_line(),
+                        previous.puzzle.url()
+                    )),
+                )
+                .await;
+        }
+
+        let moves = solver_moves(&active.puzzle);
+        let content = format!(
+            "**Daily puzzle** for {}, rated {}. {} to move, find {moves} {}!\nEveryone gets one try: answer with `{PREFIX}move` followed by all your moves. The solution is posted with the next puzzle.",
+            pgn::format_date(SystemTime::now()),
+            active.puzzle.rating,
+            color_name(active.board.side_to_move()),
+            plural(moves)
+        );
+
+        let channel = daily.channel.clone();
+        daily.current = Some(Posted {
+            puzzle: active.puzzle,
+            day,
+            solvers: Vec::new(),
+            answered: Vec::new(),
+        });
+        save(&client.cache, server, daily).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?;
+
+        send_position(
+            client,
+            &channel,
+            None,
+            &content,
+            &active.board,
+            Some(opening),
+            render_mode(client, &channel).await,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Checks an answer to the daily puzzle posted in the channel, returning whether there was
+    /// one to answer.
+    pub(super) async fn answer_daily(
+        &self,
+        client: &Client,
+        message: &Message,
+        moves: &[&str],
+    ) -> Result<bool, Error> {
+        // Only server channels have daily puzzles, anything else is left to the game.
+        let Ok(Channel::TextChannel { server, .. }) = client.fetch_channel(&message.channel).await
+        else {
+            return Ok(false);
+        };
+
+        let _guard = self.daily.lock().await;
+
+        let Some(mut daily) = load(&client.cache, &server).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?
+        else {
+            return Ok(false);
+        };
+
+        if daily.channel != message.channel {
+            return Ok(false);
+        }
+
+        let Some(posted) = daily.current.as_mut() else {
+            return Ok(false);
+        };
+
+        if posted.answered.contains(&message.author) {
+            return Err(Error::Generic(String::from(
+                "You already answered today's puzzle.",
+            )));
+        }
+
+        let needed = solver_moves(&posted.puzzle);
+
+        if moves.len() != needed {
+            return Err(Error::InvalidUsage {
+                message: format!(
+                    "The daily puzzle takes {needed} {}, write all of them in one message.",
+                    plural(needed)
+                ),
+                usage: format!("{PREFIX}move <move>..."),
+            });
+        }
+
+        let (mut active, _) = ActivePuzzle::start(posted.puzzle.clone())?;
+        let mut solved = true;
+
+        for text in moves {
+            let chess_move = san::parse_move(&active.board, text)
+                .map_err(|error| Error::Generic(format!("{text}: {error}")))?;
+            let after = active.board.make_move_new(chess_move);
+
+            // Any checkmate solves the puzzle, even if it is not the one in the solution.
+            if after.status() == BoardStatus::Checkmate {
+                break;
+            }
+
+            if active.expected_move() != Some(chess_move) {
+                solved = false;
+                break;
+            }
+
+            active.board = after;
+            active.ply += 1;
+
+            if active.is_solved() {
+                break;
+            }
+
+            active.play_solution_move()?;
+        }
+
+        posted.answered.push(message.author.clone());
+
+        let content = if solved {
+            posted.solvers.push(message.author.clone());
+
+            format!(
+                "Correct! You are solver number {}, the solution is posted with the next puzzle.",
+                posted.solvers.len()
+            )
+        } else {
+            String::from("That is not it. The solution is posted with the next puzzle.")
+        };
+
+        save(&client.cache, &server, daily).map_err(|error| Error::Fetch {
+            resource: ResourceType::Server,
+            inner: error,
+        })?;
+
+        let _ = client
+            .driver
+            .message_send(
+                &message.channel,
+                &DataMessageSend::new()
+                    .set_content(&content)
+                    .set_replies(vec![Reply {
+                        id: message.id.clone(),
+                        mention: false,
+                    }]),
+            )
+            .await;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A tactic from the Lichess puzzle database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub id: String,
+    /// The position before the opponent's move that sets up the puzzle.
+    pub fen: String,
+    /// The moves in UCI notation, starting with the opponent's.
+    pub moves: Vec<String>,
+    pub rating: u16,
+    pub rating_deviation: u16,
+    pub themes: Vec<String>,
+    pub game_url: String,
+}
+
+impl Puzzle {
+    pub fn url(&self) -> String {
+        format!("https://lichess.org/training/{}", self.id)
+    }
+
+    /// Whether the puzzle has the theme, ignoring case.
+    pub fn has_theme(&self, theme: &str) -> bool {
+        self.themes
+            .iter()
+            .any(|puzzle_theme| puzzle_theme.eq_ignore_ascii_case(theme))
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = String;
+
+    /// Reads a line of the CSV: `PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,GameUrl,OpeningTags`.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields = line.trim_end().split(',').collect::<Vec<_>>();
+
+        let [id, fen, moves, rating, rating_deviation, _, _, themes, game_url, ..] =
+            fields.as_slice()
+        else {
+            return Err(format!(
+                "expected at least 9 fields, found {}",
+                fields.len()
+            ));
+        };
+
+        let moves = moves
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        // The opponent's move and at least one answer are needed.
+        if moves.len() < 2 {
+            return Err(format!("puzzle {id} has too few moves"));
+        }
+
+        Ok(Self {
+            id: (*id).to_string(),
+            fen: (*fen).to_string(),
+            moves,
+            rating: rating
+                .parse()
+                .map_err(|_| format!("puzzle {id} has an invalid rating `{rating}`"))?,
+            rating_deviation: rating_deviation.parse().map_err(|_| {
+                format!("puzzle {id} has an invalid rating deviation `{rating_deviation}`")
+            })?,
+            themes: themes.split_whitespace().map(String::from).collect(),
+            game_url: (*game_url).to_string(),
+        })
+    }
+}
+
+/// How far from the solver's rating puzzles are looked for, widening until one is found.
+const RATING_WINDOWS: [f64; 4] = [100.0, 200.0, 400.0, 800.0];
+
+/// Picks a random puzzle close to the rating, optionally with a theme.
+pub fn pick<'a>(puzzles: &'a [Puzzle], rating: f64, theme: Option<&str>) -> Option<&'a Puzzle> {
+    let themed = puzzles
+        .iter()
+        .filter(|puzzle| theme.is_none_or(|theme| puzzle.has_theme(theme)))
+        .collect::<Vec<_>>();
+
+    let candidates = RATING_WINDOWS
+        .into_iter()
+        .map(|window| {
+            themed
+                .iter()
+                .filter(|puzzle| (f64::from(puzzle.rating) - rating).abs() <= window)
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        .find(|candidates| !candidates.is_empty())
+        .unwrap_or(themed);
+
+    candidates
+        .get(rand::random::<usize>() % candidates.len().max(1))
+        .copied()
+}
+
+/// Reads every puzzle of a CSV file, skipping the header and lines that cannot be read.
+pub fn parse(text: &str) -> Vec<Puzzle> {
+    text.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with("PuzzleId,"))
+        .filter_map(|line| match line.parse() {
+            Ok(puzzle) => Some(puzzle),
+            Err(error) => {
+                dbg!(&format!("Skipping puzzle: {error}"));
+
+                None
+            }
+        })
+        .collect()
+}
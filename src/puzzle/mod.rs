@@ -0,0 +1,597 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chess::{Board, BoardStatus, ChessMove, Color};
+use once_cell::sync::Lazy;
+use reywen::{
+    client::methods::message::DataMessageSend,
+    structures::channels::message::{Message, Reply},
+};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::{
+    commands::{Error, PREFIX},
+    game::{fen, outcome::color_name, san, send_position},
+    render, Client, ResourceType,
+};
+
+pub mod daily;
+pub mod database;
+pub mod rush;
+pub mod stats;
+
+use database::Puzzle;
+use rush::{Rush, RushMode};
+
+/// The Lichess puzzle CSV to serve puzzles from, configurable with `PUZZLE_FILE`.
+static PUZZLE_FILE: Lazy<String> =
+    Lazy::new(|| std::env::var("PUZZLE_FILE").unwrap_or_else(|_| String::from("puzzles.csv")));
+
+/// Puzzles nobody touched for this long are dropped.
+const PUZZLE_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// A puzzle someone is solving.
+struct ActivePuzzle {
+    puzzle: Puzzle,
+    board: Board,
+    /// The index of the next move of the solution.
+    ply: usize,
+    /// The move number of the position the puzzle starts from.
+    fullmove_number: u32,
+    /// The color that moved first, which is the opponent.
+    opponent: Color,
+    /// The run the puzzle is part of, in puzzle rush.
+    rush: Option<Rush>,
+    last_used: Instant,
+}
+
+/// How an answer to a puzzle went.
+enum Attempt {
+    Wrong,
+    Solved,
+    /// The move was right and the opponent replied with the given move.
+    Continue(ChessMove),
+}
+
+impl ActivePuzzle {
+    fn start(puzzle: Puzzle) -> Result<(Self, ChessMove), Error> {
+        let position = fen::parse(&puzzle.fen)
+            .map_err(|error| Error::Generic(format!("Puzzle {} is broken: {error}", puzzle.id)))?;
+        let board = position.board;
+
+        let mut active = Self {
+            board,
+            ply: 0,
+            fullmove_number: position.fullmove_number,
+            opponent: board.side_to_move(),
+            rush: None,
+            last_used: Instant::now(),
+            puzzle,
+        };
+        let opening = active.play_solution_move()?;
+
+        Ok((active, opening))
+    }
+
+    /// Plays the next move of the solution.
+    fn play_solution_move(&mut self) -> Result<ChessMove, Error> {
+        let chess_move = self
+            .puzzle
+            .moves
+            .get(self.ply)
+            .and_then(|text| san::parse_uci(&self.board, text).ok())
+            .ok_or_else(|| {
+                Error::Generic(format!("Puzzle {} has an illegal move.", self.puzzle.id))
+            })?;
+
+        self.board = self.board.make_move_new(chess_move);
+        self.ply += 1;
+
+        Ok(chess_move)
+    }
+
+    fn expected_move(&self) -> Option<ChessMove> {
+        self.puzzle
+            .moves
+            .get(self.ply)
+            .and_then(|text| san::parse_uci(&self.board, text).ok())
+    }
+
+    /// The rest of the solution in algebraic notation with move numbers.
+    fn remaining_line(&self) -> String {
+        let mut board = self.board;
+        let mut tokens = Vec::new();
+
+        for (ply, text) in self.puzzle.moves.iter().enumerate().skip(self.ply) {
+            let Ok(chess_move) = san::parse_uci(&board, text) else {
+                break;
+            };
+
+            // The puzzle starts at the opponent's move, so plies count from that color.
+            let plies_from_white = ply + usize::from(self.opponent == Color::Black);
+            let number =
+                u32::try_from(plies_from_white / 2).unwrap_or_default() + self.fullmove_number;
+
+            if board.side_to_move() == Color::White {
+                tokens.push(format!("{number}."));
+            } else if ply == self.ply {
+                tokens.push(format!("{number}..."));
+            }
+
+            tokens.push(san::format(&board, chess_move));
+            board = board.make_move_new(chess_move);
+        }
+
+        tokens.join(" ")
+    }
+
+    fn is_solved(&self) -> bool {
+        self.ply >= self.puzzle.moves.len()
+    }
+}
+
+/// Serves puzzles from the puzzle file, keeping one puzzle per user and channel.
+#[derive(Clone, Default)]
+pub struct PuzzleManager {
+    database: Arc<OnceCell<Vec<Puzzle>>>,
+    active: Arc<Mutex<HashMap<(String, String), ActivePuzzle>>>,
+    /// Held while a daily puzzle is posted or answered, so answers are not lost.
+    daily: Arc<Mutex<()>>,
+}
+
+impl PuzzleManager {
+    /// The puzzles, read from the puzzle file the first time they are needed.
+    async fn puzzles(&self) -> Result<&[Puzzle], Error> {
+        self.database
+            .get_or_try_init(|| async {
+                let text = tokio::fs::read_to_string(&*PUZZLE_FILE)
+                    .await
+                    .map_err(|error| {
+                        Error::Generic(format!(
+                            "Could not read the puzzle file `{}`: {error}",
+                            *PUZZLE_FILE
+                        ))
+                    })?;
+
+                let puzzles = tokio::task::spawn_blocking(move || database::parse(&text))
+                    .await
+                    .map_err(|error| Error::Generic(error.to_string()))?;
+
+                if puzzles.is_empty() {
+                    return Err(Error::Generic(format!(
+                        "The puzzle file `{}` has no puzzles.",
+                        *PUZZLE_FILE
+                    )));
+                }
+
+                Ok(puzzles)
+            })
+            .await
+            .map(Vec::as_slice)
+    }
+
+    /// Gives the author a puzzle close to their puzzle rating, replacing the one they were solving.
+    pub async fn start(
+        &self,
+        client: &Client,
+        message: &Message,
+        theme: Option<&str>,
+    ) -> Result<(), Error> {
+        let rating = stats::load(&client.cache, &message.author)
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::User,
+                inner: error,
+            })?
+            .rating;
+
+        let puzzles = self.puzzles().await?;
+        let puzzle = database::pick(puzzles, rating.rating, theme)
+            .ok_or_else(|| {
+                Error::Generic(format!(
+                    "There are no puzzles with the theme `{}`.",
+                    theme.unwrap_or_default()
+                ))
+            })?
+            .clone();
+
+        let (active, opening) = ActivePuzzle::start(puzzle)?;
+        let content = format!(
+            "Puzzle `{}`, rated {}. Find the best move for {}! Answer with `{PREFIX}move`.",
+            active.puzzle.id,
+            active.puzzle.rating,
+            color_name(active.board.side_to_move())
+        );
+
+        self.present(client, message, active, opening, &content)
+            .await;
+
+        Ok(())
+    }
+
+    /// Starts a puzzle rush for the author, replacing the puzzle they were solving.
+    pub async fn start_rush(
+        &self,
+        client: &Client,
+        message: &Message,
+        mode: RushMode,
+    ) -> Result<(), Error> {
+        let server = client
+            .fetch_server_id(&message.channel)
+            .await
+            .map_err(|error| Error::Fetch {
+                resource: ResourceType::Server,
+                inner: error,
+            })?;
+
+        let rush = Rush::new(mode, server);
+        let rush_id = rush.id;
+        let (active, opening) = self.next_rush_puzzle(rush).await?;
+
+        let content = format!(
+            "Puzzle rush ({mode})! Solve as many puzzles as you can{}, {} wrong answers end the run. Answer with `{PREFIX}move`.\nPuzzle 1, {} to move.",
+            mode.time_limit().map_or_else(String::new, |limit| format!(
+                " in {} minutes",
+                limit.as_secs() / 60
+            )),
+            rush::STRIKES,
+            color_name(active.board.side_to_move())
+        );
+
+        self.present(client, message, active, opening, &content)
+            .await;
+
+        // Runs against the clock end when time is up, even if nobody answers.
+        if let Some(limit) = mode.time_limit() {
+            let this = self.clone();
+            let client = client.clone();
+            let key = (message.channel.clone(), message.author.clone());
+            let reply_to = message.id.clone();
+
+            tokio::spawn(async move {
+                tokio::time::sleep(limit).await;
+
+                let mut active_puzzles = this.active.lock().await;
+
+                if active_puzzles
+                    .get(&key)
+                    .and_then(|active| active.rush.as_ref())
+                    .is_some_and(|rush| rush.id == rush_id)
+                {
+                    let rush = active_puzzles.remove(&key).and_then(|active| active.rush);
+                    drop(active_puzzles);
+
+                    if let Some(rush) = rush {
+                        let (channel, user_id) = &key;
+
+                        end_rush(&client, channel, &reply_to, user_id, &rush, "Time's up!").await;
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Picks the next, harder puzzle of a run.
+    async fn next_rush_puzzle(&self, rush: Rush) -> Result<(ActivePuzzle, ChessMove), Error> {
+        let puzzles = self.puzzles().await?;
+        let puzzle = database::pick(puzzles, rush.next_rating(), None)
+            .ok_or_else(|| Error::Generic(String::from("There are no puzzles.")))?
+            .clone();
+
+        let (mut active, opening) = ActivePuzzle::start(puzzle)?;
+        active.rush = Some(rush);
+
+        Ok((active, opening))
+    }
+
+    /// Makes the puzzle the author's current one and shows it.
+    async fn present(
+        &self,
+        client: &Client,
+        message: &Message,
+        active: ActivePuzzle,
+        opening: ChessMove,
+        content: &str,
+    ) {
+        let board = active.board;
+
+        let mut active_puzzles = self.active.lock().await;
+        active_puzzles.retain(|_, active| active.last_used.elapsed() < PUZZLE_LIFETIME);
+        active_puzzles.insert((message.channel.clone(), message.author.clone()), active);
+        drop(active_puzzles);
+
+        send_position(
+            client,
+            &message.channel,
+            Some(&message.id),
+            content,
+            &board,
+            Some(opening),
+            render_mode(client, &message.channel).await,
+        )
+        .await;
+    }
+
+    /// Handles `move` if the author is solving a puzzle here, or answering the channel's daily
+    /// puzzle, and not playing a game, returning whether it was handled.
+    pub async fn handle_message(&self, client: &Client, message: &Message) -> Result<bool, Error> {
+        let Some(content) = &message.content else {
+            return Ok(false);
+        };
+
+        let mut args = content.split_whitespace();
+
+        if !args
+            .next()
+            .is_some_and(|command| *command == format!("{PREFIX}move"))
+        {
+            return Ok(false);
+        }
+
+        if client
+            .games
+            .is_playing(&message.channel, &message.author)
+            .await
+        {
+            return Ok(false);
+        }
+
+        let key = (message.channel.clone(), message.author.clone());
+        let mut active_puzzles = self.active.lock().await;
+
+        if !active_puzzles.contains_key(&key) {
+            drop(active_puzzles);
+
+            return self
+                .answer_daily(client, message, &args.collect::<Vec<_>>())
+                .await;
+        }
+
+        let Some(text) = args.next() else {
+            return Err(Error::InvalidUsage {
+                message: String::from("Expected a move argument."),
+                usage: format!("{PREFIX}move <move>"),
+            });
+        };
+
+        let Some(active) = active_puzzles.get_mut(&key) else {
+            return Ok(false);
+        };
+
+        // An answer after the time ran out only ends the run.
+        if active.rush.as_ref().is_some_and(Rush::is_over) {
+            let rush = active_puzzles.remove(&key).and_then(|active| active.rush);
+            drop(active_puzzles);
+
+            if let Some(rush) = rush {
+                end_rush(
+                    client,
+                    &message.channel,
+                    &message.id,
+                    &message.author,
+                    &rush,
+                    "Time's up!",
+                )
+                .await;
+            }
+
+            return Ok(true);
+        }
+
+        let chess_move = san::parse_move(&active.board, text)
+            .map_err(|error| Error::Generic(error.to_string()))?;
+        let before = active.board;
+        let after = before.make_move_new(chess_move);
+        active.last_used = Instant::now();
+
+        // Any checkmate solves the puzzle, even if it is not the one in the solution.
+        let is_correct =
+            active.expected_move() == Some(chess_move) || after.status() == BoardStatus::Checkmate;
+
+        let attempt = if is_correct {
+            active.board = after;
+            active.ply += 1;
+
+            if active.is_solved() || after.status() == BoardStatus::Checkmate {
+                Attempt::Solved
+            } else {
+                Attempt::Continue(active.play_solution_move()?)
+            }
+        } else {
+            Attempt::Wrong
+        };
+
+        if let Attempt::Continue(reply) = attempt {
+            let board = active.board;
+            drop(active_puzzles);
+
+            send_position(
+                client,
+                &message.channel,
+                Some(&message.id),
+                &format!(
+                    "Correct! {} to move, keep going.",
+                    color_name(board.side_to_move())
+                ),
+                &board,
+                Some(reply),
+                render_mode(client, &message.channel).await,
+            )
+            .await;
+
+            return Ok(true);
+        }
+
+        let Some(active) = active_puzzles.remove(&key) else {
+            return Ok(true);
+        };
+        drop(active_puzzles);
+
+        if let Some(rush) = active.rush {
+            self.continue_rush(client, message, rush, &active, chess_move, &attempt)
+                .await?;
+
+            return Ok(true);
+        }
+
+        let content = match attempt {
+            Attempt::Solved => format!(
+                "Solved! Themes: {}{}\nFrom <{}>",
+                active.puzzle.themes.join(", "),
+                rating_change(client, &message.author, &active.puzzle, true),
+                active.puzzle.game_url
+            ),
+            Attempt::Wrong | Attempt::Continue(_) => format!(
+                "{} is not it. The solution was {}{}\n<{}>",
+                san::format(&before, chess_move),
+                active.remaining_line(),
+                rating_change(client, &message.author, &active.puzzle, false),
+                active.puzzle.url()
+            ),
+        };
+
+        send_position(
+            client,
+            &message.channel,
+            Some(&message.id),
+            &content,
+            &active.board,
+            matches!(attempt, Attempt::Solved).then_some(chess_move),
+            render_mode(client, &message.channel).await,
+        )
+        .await;
+
+        Ok(true)
+    }
+
+    /// Scores a finished puzzle of a run, then either serves the next one or ends the run.
+    async fn continue_rush(
+        &self,
+        client: &Client,
+        message: &Message,
+        mut rush: Rush,
+        active: &ActivePuzzle,
+        chess_move: ChessMove,
+        attempt: &Attempt,
+    ) -> Result<(), Error> {
+        let verdict = if matches!(attempt, Attempt::Solved) {
+            rush.score += 1;
+
+            String::from("Correct!")
+        } else {
+            rush.strikes += 1;
+
+            format!(
+                "{} is not it, the solution was {}. Strike {}/{}.",
+                san::format(&active.board, chess_move),
+                active.remaining_line(),
+                rush.strikes,
+                rush::STRIKES
+            )
+        };
+
+        if rush.is_over() {
+            end_rush(
+                client,
+                &message.channel,
+                &message.id,
+                &message.author,
+                &rush,
+                &verdict,
+            )
+            .await;
+
+            return Ok(());
+        }
+
+        let time_left = rush
+            .mode
+            .time_limit()
+            .map(|limit| limit.saturating_sub(rush.started_at.elapsed()));
+        let content = format!(
+            "{verdict} Score: {}{}\nPuzzle {}, ",
+            rush.score,
+            time_left.map_or_else(String::new, |left| format!(", {}s left", left.as_secs())),
+            rush.score + u32::from(rush.strikes) + 1
+        );
+
+        let (next, opening) = self.next_rush_puzzle(rush).await?;
+        let content = format!(
+            "{content}{} to move.",
+            color_name(next.board.side_to_move())
+        );
+
+        self.present(client, message, next, opening, &content).await;
+
+        Ok(())
+    }
+}
+
+/// Stores the score of a finished run and announces it with the best scores.
+async fn end_rush(
+    client: &Client,
+    channel: &str,
+    reply_to: &str,
+    user_id: &str,
+    rush: &Rush,
+    verdict: &str,
+) {
+    let bests = match rush::record(&client.cache, user_id, rush) {
+        Ok(bests) => {
+            let server_best = bests.server.map_or_else(String::new, |(user, score)| {
+                format!(", server best: {score} by <@{user}>")
+            });
+
+            format!("\nPersonal best: {}{server_best}", bests.personal)
+        }
+        Err(error) => {
+            dbg!(&format!("Failed to record rush score: {error:?}"));
+
+            String::new()
+        }
+    };
+
+    let _ = client
+        .driver
+        .message_send(
+            channel,
+            &DataMessageSend::new()
+                .set_content(&format!(
+                    "{verdict} The {} rush is over with a score of {}.{bests}",
+                    rush.mode, rush.score
+                ))
+                .set_replies(vec![Reply {
+                    id: reply_to.to_string(),
+                    mention: true,
+                }]),
+        )
+        .await;
+}
+
+/// Rates the attempt, describing how the user's puzzle rating changed.
+fn rating_change(client: &Client, user_id: &str, puzzle: &Puzzle, solved: bool) -> String {
+    match stats::record(&client.cache, user_id, puzzle, solved) {
+        Ok((old, new)) => format!(
+            "\nPuzzle rating: {} ({:+.0})",
+            new.rating,
+            new.rating.rating - old.rating.rating
+        ),
+        Err(error) => {
+            dbg!(&format!("Failed to record puzzle attempt: {error:?}"));
+
+            String::new()
+        }
+    }
+}
+
+async fn render_mode(client: &Client, channel: &str) -> render::RenderMode {
+    client
+        .fetch_server_id(channel)
+        .await
+        .and_then(|server| render::server_mode(&client.cache, &server))
+        .unwrap_or_default()
+}
@@ -0,0 +1,136 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use redis::Commands;
+
+/// How many wrong answers end a run.
+pub const STRIKES: u8 = 3;
+
+/// The rating of the first puzzle of a run, and how much harder every solved puzzle makes the next.
+const START_RATING: f64 = 600.0;
+const RATING_STEP: f64 = 75.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RushMode {
+    ThreeMinutes,
+    FiveMinutes,
+    /// No clock, the run lasts until the third strike.
+    Survival,
+}
+
+impl RushMode {
+    pub const fn time_limit(self) -> Option<Duration> {
+        match self {
+            Self::ThreeMinutes => Some(Duration::from_secs(3 * 60)),
+            Self::FiveMinutes => Some(Duration::from_secs(5 * 60)),
+            Self::Survival => None,
+        }
+    }
+}
+
+impl FromStr for RushMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "3m" | "3" => Ok(Self::ThreeMinutes),
+            "5m" | "5" => Ok(Self::FiveMinutes),
+            "survival" => Ok(Self::Survival),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for RushMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ThreeMinutes => "3m",
+            Self::FiveMinutes => "5m",
+            Self::Survival => "survival",
+        })
+    }
+}
+
+/// A run of puzzles getting harder with every one solved.
+#[derive(Debug, Clone)]
+pub struct Rush {
+    pub mode: RushMode,
+    /// Tells runs apart, so the timer of an old run does not end a new one.
+    pub id: u64,
+    pub server: String,
+    pub started_at: Instant,
+    pub score: u32,
+    pub strikes: u8,
+}
+
+impl Rush {
+    pub fn new(mode: RushMode, server: String) -> Self {
+        Self {
+            mode,
+            id: rand::random(),
+            server,
+            started_at: Instant::now(),
+            score: 0,
+            strikes: 0,
+        }
+    }
+
+    /// The rating to look for the next puzzle around.
+    pub fn next_rating(&self) -> f64 {
+        RATING_STEP.mul_add(f64::from(self.score), START_RATING)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.strikes >= STRIKES
+            || self
+                .mode
+                .time_limit()
+                .is_some_and(|limit| self.started_at.elapsed() >= limit)
+    }
+}
+
+/// The best scores once a run is over.
+pub struct Bests {
+    pub personal: u32,
+    /// The user with the best score in the server, and that score.
+    pub server: Option<(String, u32)>,
+}
+
+fn personal_key(mode: RushMode) -> String {
+    format!("rush-bests:{mode}")
+}
+
+fn server_key(server: &str, mode: RushMode) -> String {
+    format!("rush-bests:{server}:{mode}")
+}
+
+/// Stores the score of a finished run if it beats the user's best, returning the best scores.
+pub fn record(cache: &redis::Client, user_id: &str, rush: &Rush) -> crate::Result<Bests> {
+    let mut conn = cache.get_connection()?;
+
+    let personal = conn
+        .hget::<_, _, Option<u32>>(personal_key(rush.mode), user_id)?
+        .unwrap_or_default()
+        .max(rush.score);
+
+    let server_personal = conn
+        .zscore::<_, _, Option<u32>>(server_key(&rush.server, rush.mode), user_id)?
+        .unwrap_or_default()
+        .max(rush.score);
+
+    conn.hset::<_, _, _, ()>(personal_key(rush.mode), user_id, personal)?;
+    conn.zadd::<_, _, _, ()>(
+        server_key(&rush.server, rush.mode),
+        user_id,
+        server_personal,
+    )?;
+
+    let server = conn
+        .zrevrange_withscores::<_, Vec<(String, u32)>>(server_key(&rush.server, rush.mode), 0, 0)?
+        .into_iter()
+        .next();
+
+    Ok(Bests { personal, server })
+}
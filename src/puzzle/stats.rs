@@ -0,0 +1,77 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+use crate::{game::rating::Rating, RedisPuzzleStats};
+
+use super::database::Puzzle;
+
+const PUZZLE_STATS_KEY: &str = "puzzle-stats";
+
+/// How many past ratings are kept for the history.
+const HISTORY_LENGTH: usize = 20;
+
+/// A user's puzzle rating and how their attempts went.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PuzzleStats {
+    pub rating: Rating,
+    pub attempts: u32,
+    pub solved: u32,
+    /// The rating after each of the latest attempts, as seconds since the Unix epoch and rating.
+    pub history: Vec<(u64, f64)>,
+}
+
+impl PuzzleStats {
+    /// The percentage of attempts solved, rounded down.
+    pub const fn solve_rate(&self) -> u32 {
+        match self.attempts {
+            0 => 0,
+            attempts => self.solved * 100 / attempts,
+        }
+    }
+}
+
+pub fn load(cache: &redis::Client, user_id: &str) -> crate::Result<PuzzleStats> {
+    let mut conn = cache.get_connection()?;
+    let stats: Option<RedisPuzzleStats> = conn.hget(PUZZLE_STATS_KEY, user_id)?;
+
+    Ok(stats
+        .map(|RedisPuzzleStats(stats)| stats)
+        .unwrap_or_default())
+}
+
+/// Rates an attempt like a game against the puzzle, returning the old and new statistics.
+pub fn record(
+    cache: &redis::Client,
+    user_id: &str,
+    puzzle: &Puzzle,
+    solved: bool,
+) -> crate::Result<(PuzzleStats, PuzzleStats)> {
+    let old = load(cache, user_id)?;
+    let opponent = Rating {
+        rating: f64::from(puzzle.rating),
+        deviation: f64::from(puzzle.rating_deviation),
+        ..Rating::default()
+    };
+
+    let mut new = old.clone();
+    new.rating = old.rating.update(opponent, if solved { 1.0 } else { 0.0 });
+    new.attempts += 1;
+    new.solved += u32::from(solved);
+    new.history.push((
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+        new.rating.rating,
+    ));
+
+    if new.history.len() > HISTORY_LENGTH {
+        new.history.drain(..new.history.len() - HISTORY_LENGTH);
+    }
+
+    let mut conn = cache.get_connection()?;
+    conn.hset::<_, _, _, ()>(PUZZLE_STATS_KEY, user_id, RedisPuzzleStats(new.clone()))?;
+
+    Ok((old, new))
+}
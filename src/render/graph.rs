@@ -0,0 +1,58 @@
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgb, RgbImage};
+
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 200;
+
+const WHITE_AREA: Rgb<u8> = Rgb([230, 230, 230]);
+const BLACK_AREA: Rgb<u8> = Rgb([60, 57, 54]);
+const MIDDLE_LINE: Rgb<u8> = Rgb([150, 150, 150]);
+const MARKER: Rgb<u8> = Rgb([224, 82, 82]);
+
+/// Draws how a game went as a PNG image, from white's winning chances after every move between
+/// -1 and 1. The marked moves get a red line. Returns `None` if the image could not be encoded.
+pub fn render(chances: &[f64], marked: &[usize]) -> Option<Vec<u8>> {
+    let mut image = RgbImage::from_pixel(WIDTH, HEIGHT, BLACK_AREA);
+    let middle = HEIGHT / 2;
+
+    let moves = u64::try_from(chances.len()).unwrap_or_default();
+    // The index of the move shown in each column.
+    let move_at =
+        |x: u32| usize::try_from(u64::from(x) * moves / u64::from(WIDTH)).unwrap_or_default();
+
+    for x in 0..WIDTH {
+        let index = move_at(x);
+        let chance = chances
+            .get(index)
+            .copied()
+            .unwrap_or_default()
+            .clamp(-1.0, 1.0);
+        let top = f64::from(middle) * (1.0 - chance);
+        // The first row below the line, found without a lossy cast.
+        let top = (0..=HEIGHT)
+            .find(|y| f64::from(*y) >= top)
+            .unwrap_or(HEIGHT);
+
+        for y in top..HEIGHT {
+            image.put_pixel(x, y, WHITE_AREA);
+        }
+
+        let is_marked = marked.contains(&index) && (x == 0 || move_at(x - 1) != index);
+
+        if is_marked {
+            for y in 0..HEIGHT {
+                image.put_pixel(x, y, MARKER);
+            }
+        } else {
+            image.put_pixel(x, middle, MIDDLE_LINE);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+        .ok()?;
+
+    Some(bytes)
+}
@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Color, Square, EMPTY};
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+
+pub mod graph;
+pub mod png;
+pub mod text;
+
+const RENDER_MODES_KEY: &str = "render-modes";
+
+/// How boards are sent to the chat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderMode {
+    #[default]
+    Image,
+    Text,
+}
+
+impl FromStr for RenderMode {
+    type Err = ();
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "image" | "png" => Ok(Self::Image),
+            "text" | "unicode" => Ok(Self::Text),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Image => "image",
+            Self::Text => "text",
+        })
+    }
+}
+
+/// Gets the render mode a server has chosen, defaulting to images.
+pub fn server_mode(cache: &redis::Client, server: &str) -> crate::Result<RenderMode> {
+    let mut conn = cache.get_connection()?;
+    let mode: Option<String> = conn.hget(RENDER_MODES_KEY, server)?;
+
+    Ok(mode.and_then(|mode| mode.parse().ok()).unwrap_or_default())
+}
+
+pub fn set_server_mode(cache: &redis::Client, server: &str, mode: RenderMode) -> crate::Result<()> {
+    let mut conn = cache.get_connection()?;
+    conn.hset::<_, _, _, ()>(RENDER_MODES_KEY, server, mode.to_string())?;
+
+    Ok(())
+}
+
+/// Everything needed to draw a position.
+#[derive(Clone, Copy)]
+pub struct BoardView<'a> {
+    pub board: &'a Board,
+    pub last_move: Option<ChessMove>,
+    /// The color at the bottom of the board.
+    pub orientation: Color,
+}
+
+impl<'a> BoardView<'a> {
+    /// Creates a view oriented for the player to move.
+    pub fn new(board: &'a Board, last_move: Option<ChessMove>) -> Self {
+        Self {
+            board,
+            last_move,
+            orientation: board.side_to_move(),
+        }
+    }
+
+    pub fn checked_king(&self) -> Option<Square> {
+        (*self.board.checkers() != EMPTY)
+            .then_some(self.board.king_square(self.board.side_to_move()))
+    }
+
+    pub fn is_highlighted(&self, square: Square) -> bool {
+        self.last_move.is_some_and(|last_move| {
+            last_move.get_source() == square || last_move.get_dest() == square
+        })
+    }
+
+    /// The ranks from top to bottom and the files from left to right.
+    pub fn ranks_and_files(&self) -> ([usize; 8], [usize; 8]) {
+        match self.orientation {
+            Color::White => ([7, 6, 5, 4, 3, 2, 1, 0], [0, 1, 2, 3, 4, 5, 6, 7]),
+            Color::Black => ([0, 1, 2, 3, 4, 5, 6, 7], [7, 6, 5, 4, 3, 2, 1, 0]),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::io::Cursor;
+
+use chess::{Color, File, Piece, Rank, Square};
+use image::{ImageOutputFormat, Rgb, RgbImage};
+
+use super::BoardView;
+
+const SQUARE_SIZE: u32 = 60;
+const MARGIN: u32 = 20;
+const PIECE_SCALE: u32 = 3;
+const LABEL_SCALE: u32 = 2;
+
+const LIGHT_SQUARE: Rgb<u8> = Rgb([240, 217, 181]);
+const DARK_SQUARE: Rgb<u8> = Rgb([181, 136, 99]);
+const LIGHT_HIGHLIGHT: Rgb<u8> = Rgb([205, 210, 106]);
+const DARK_HIGHLIGHT: Rgb<u8> = Rgb([170, 162, 58]);
+const CHECK: Rgb<u8> = Rgb([224, 82, 82]);
+const BACKGROUND: Rgb<u8> = Rgb([49, 46, 43]);
+const LABEL: Rgb<u8> = Rgb([220, 220, 220]);
+
+const WHITE_FILL: Rgb<u8> = Rgb([250, 250, 250]);
+const WHITE_OUTLINE: Rgb<u8> = Rgb([20, 20, 20]);
+const BLACK_FILL: Rgb<u8> = Rgb([50, 50, 50]);
+const BLACK_OUTLINE: Rgb<u8> = Rgb([0, 0, 0]);
+
+type Sprite = [&'static str; 16];
+
+const PAWN: Sprite = [
+    "................",
+    "................",
+    "................",
+    "......####......",
+    ".....######.....",
+    ".....######.....",
+    "......####......",
+    ".....######.....",
+    "......####......",
+    "......####......",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+    "................",
+];
+
+const KNIGHT: Sprite = [
+    "................",
+    ".......#.#......",
+    "......######....",
+    ".....########...",
+    "....##########..",
+    "...###.#######..",
+    "...####..#####..",
+    "....##..######..",
+    ".......######...",
+    "......#######...",
+    ".....########...",
+    ".....########...",
+    "....##########..",
+    "...############.",
+    "...############.",
+    "................",
+];
+
+const BISHOP: Sprite = [
+    "................",
+    ".......##.......",
+    "......####......",
+    ".....###.##.....",
+    ".....##.###.....",
+    ".....######.....",
+    "......####......",
+    ".......##.......",
+    "......####......",
+    ".....######.....",
+    "......####......",
+    "......####......",
+    "....########....",
+    "...##########...",
+    "...##########...",
+    "................",
+];
+
+const ROOK: Sprite = [
+    "................",
+    "................",
+    "...##.####.##...",
+    "...##########...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "..############..",
+    "..############..",
+    "................",
+    "................",
+];
+
+const QUEEN: Sprite = [
+    "................",
+    "..#....##....#..",
+    "..##..####..##..",
+    "..###.####.###..",
+    "..############..",
+    "...##########...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "..############..",
+    "..############..",
+    "................",
+    "................",
+];
+
+const KING: Sprite = [
+    ".......##.......",
+    "......####......",
+    ".......##.......",
+    "....##.##.##....",
+    "...##########...",
+    "...##########...",
+    "...##########...",
+    "....########....",
+    ".....######.....",
+    ".....######.....",
+    "....########....",
+    "...##########...",
+    "..############..",
+    "..############..",
+    "................",
+    "................",
+];
+
+type Glyph = [&'static str; 5];
+
+const FILE_GLYPHS: [Glyph; 8] = [
+    ["...", ".##", "#.#", "#.#", ".##"],
+    ["#..", "##.", "#.#", "#.#", "##."],
+    ["...", ".##", "#..", "#..", ".##"],
+    ["..#", ".##", "#.#", "#.#", ".##"],
+    ["...", ".#.", "###", "#..", ".##"],
+    [".##", "#..", "###", "#..", "#.."],
+    [".##", "#.#", ".##", "..#", "##."],
+    ["#..", "##.", "#.#", "#.#", "#.#"],
+];
+
+const RANK_GLYPHS: [Glyph; 8] = [
+    [".#.", "##.", ".#.", ".#.", "###"],
+    ["##.", "..#", ".#.", "#..", "###"],
+    ["##.", "..#", ".#.", "..#", "##."],
+    ["#.#", "#.#", "###", "..#", "..#"],
+    ["###", "#..", "##.", "..#", "##."],
+    [".##", "#..", "###", "#.#", "###"],
+    ["###", "..#", ".#.", ".#.", ".#."],
+    ["###", "#.#", "###", "#.#", "###"],
+];
+
+/// Draws the board as a PNG image, or returns `None` if it could not be encoded.
+pub fn render(view: &BoardView) -> Option<Vec<u8>> {
+    let size = MARGIN + SQUARE_SIZE * 8;
+    let mut image = RgbImage::from_pixel(size, size, BACKGROUND);
+    let (ranks, files) = view.ranks_and_files();
+    let checked_king = view.checked_king();
+
+    for (row, rank) in (0u32..).zip(ranks) {
+        for (column, file) in (0u32..).zip(files) {
+            let square = Square::make_square(Rank::from_index(rank), File::from_index(file));
+            let is_light = (rank + file) % 2 == 1;
+            let x = MARGIN + column * SQUARE_SIZE;
+            let y = row * SQUARE_SIZE;
+
+            let color = if checked_king == Some(square) {
+                CHECK
+            } else {
+                match (view.is_highlighted(square), is_light) {
+                    (true, true) => LIGHT_HIGHLIGHT,
+                    (true, false) => DARK_HIGHLIGHT,
+                    (false, true) => LIGHT_SQUARE,
+                    (false, false) => DARK_SQUARE,
+                }
+            };
+
+            fill_rect(&mut image, x, y, SQUARE_SIZE, SQUARE_SIZE, color);
+
+            if let (Some(piece), Some(piece_color)) =
+                (view.board.piece_on(square), view.board.color_on(square))
+            {
+                draw_piece(&mut image, x, y, piece, piece_color);
+            }
+        }
+    }
+
+    let label_offset = (SQUARE_SIZE - 3 * LABEL_SCALE) / 2;
+
+    for (index, rank) in (0u32..).zip(ranks) {
+        draw_glyph(
+            &mut image,
+            (MARGIN - 3 * LABEL_SCALE) / 2,
+            index * SQUARE_SIZE + (SQUARE_SIZE - 5 * LABEL_SCALE) / 2,
+            &RANK_GLYPHS[rank],
+        );
+    }
+
+    for (index, file) in (0u32..).zip(files) {
+        draw_glyph(
+            &mut image,
+            MARGIN + index * SQUARE_SIZE + label_offset,
+            SQUARE_SIZE * 8 + (MARGIN - 5 * LABEL_SCALE) / 2,
+            &FILE_GLYPHS[file],
+        );
+    }
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+        .ok()?;
+
+    Some(bytes)
+}
+
+fn fill_rect(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for dy in 0..height {
+        for dx in 0..width {
+            image.put_pixel(x + dx, y + dy, color);
+        }
+    }
+}
+
+fn draw_piece(image: &mut RgbImage, x: u32, y: u32, piece: Piece, color: Color) {
+    let sprite = match piece {
+        Piece::Pawn => &PAWN,
+        Piece::Knight => &KNIGHT,
+        Piece::Bishop => &BISHOP,
+        Piece::Rook => &ROOK,
+        Piece::Queen => &QUEEN,
+        Piece::King => &KING,
+    };
+
+    let (fill, outline) = match color {
+        Color::White => (WHITE_FILL, WHITE_OUTLINE),
+        Color::Black => (BLACK_FILL, BLACK_OUTLINE),
+    };
+
+    let is_set = |row: i32, column: i32| {
+        usize::try_from(row)
+            .ok()
+            .zip(usize::try_from(column).ok())
+            .and_then(|(row, column)| sprite.get(row)?.as_bytes().get(column))
+            .is_some_and(|pixel| *pixel == b'#')
+    };
+
+    let offset = (SQUARE_SIZE - 16 * PIECE_SCALE) / 2;
+
+    for (row, line) in (0i32..).zip(sprite) {
+        for (column, pixel) in (0i32..).zip(line.bytes()) {
+            if pixel != b'#' {
+                continue;
+            }
+
+            let is_edge = !(is_set(row - 1, column)
+                && is_set(row + 1, column)
+                && is_set(row, column - 1)
+                && is_set(row, column + 1));
+
+            fill_rect(
+                image,
+                x + offset + column.unsigned_abs() * PIECE_SCALE,
+                y + offset + row.unsigned_abs() * PIECE_SCALE,
+                PIECE_SCALE,
+                PIECE_SCALE,
+                if is_edge { outline } else { fill },
+            );
+        }
+    }
+}
+
+fn draw_glyph(image: &mut RgbImage, x: u32, y: u32, glyph: &Glyph) {
+    for (row, line) in (0u32..).zip(glyph) {
+        for (column, pixel) in (0u32..).zip(line.bytes()) {
+            if pixel == b'#' {
+                fill_rect(
+                    image,
+                    x + column * LABEL_SCALE,
+                    y + row * LABEL_SCALE,
+                    LABEL_SCALE,
+                    LABEL_SCALE,
+                    LABEL,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use chess::{Color, File, Piece, Rank, Square};
+
+use super::BoardView;
+
+/// Draws the board as a Unicode grid inside a code block.
+pub fn render(view: &BoardView) -> String {
+    let (ranks, files) = view.ranks_and_files();
+    let mut text = String::from("```\n");
+
+    for rank in ranks {
+        text.push_str(&format!("{} ", rank + 1));
+
+        for file in files {
+            let square = Square::make_square(Rank::from_index(rank), File::from_index(file));
+
+            text.push(
+                match (view.board.piece_on(square), view.board.color_on(square)) {
+                    (Some(piece), Some(color)) => piece_char(piece, color),
+                    _ if view.is_highlighted(square) => '○',
+                    _ => '·',
+                },
+            );
+            text.push(' ');
+        }
+
+        text.push('\n');
+    }
+
+    text.push_str("  ");
+    for file in files {
+        text.push(char::from(b'a' + u8::try_from(file).unwrap_or_default()));
+        text.push(' ');
+    }
+    text.push('\n');
+
+    if let Some(last_move) = view.last_move {
+        text.push_str(&format!(
+            "\nLast move: {} → {}",
+            last_move.get_source(),
+            last_move.get_dest()
+        ));
+    }
+
+    if view.checked_king().is_some() {
+        text.push_str("\nCheck!");
+    }
+
+    text.push_str("\n```");
+
+    text
+}
+
+const fn piece_char(piece: Piece, color: Color) -> char {
+    match (color, piece) {
+        (Color::White, Piece::Pawn) => '♙',
+        (Color::White, Piece::Knight) => '♘',
+        (Color::White, Piece::Bishop) => '♗',
+        (Color::White, Piece::Rook) => '♖',
+        (Color::White, Piece::Queen) => '♕',
+        (Color::White, Piece::King) => '♔',
+        (Color::Black, Piece::Pawn) => '♟',
+        (Color::Black, Piece::Knight) => '♞',
+        (Color::Black, Piece::Bishop) => '♝',
+        (Color::Black, Piece::Rook) => '♜',
+        (Color::Black, Piece::Queen) => '♛',
+        (Color::Black, Piece::King) => '♚',
+    }
+}